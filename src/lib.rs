@@ -1,53 +1,244 @@
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
 use std::error::Error;
 use std::fmt::Display;
-use std::io::{self, BufRead};
+use std::io;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
 
 #[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum GameStatus {
     Win,
     Lose,
     Continue,
 }
 
+impl Display for GameStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            GameStatus::Win => "Win",
+            GameStatus::Lose => "Lose",
+            GameStatus::Continue => "Continue",
+        };
+        f.write_str(name)
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum BoardError {
-    InvalidCharacter(char),
+    /// A character that isn't part of the board format, at `(row, col)`
+    InvalidCharacter {
+        ch: char,
+        row: usize,
+        col: usize,
+    },
+    /// The board doesn't fit the width and height it was given
     InvalidSize,
+    /// The board has no lines, or only blank ones
+    EmptyBoard,
+    /// The first line of the board is blank, so it has no width
+    EmptyFirstRow,
+    /// Line `row` is `found` characters long, but the first is `expected`
+    RowLengthMismatch {
+        row: usize,
+        expected: usize,
+        found: usize,
+    },
     NoMinotaur,
     NoTheseus,
     NoGoal,
     MultipleMinotaur,
     MultipleTheseus,
     MultipleGoal,
+    MultipleKey,
+    MultipleDoor,
+    /// Portals come in pairs; a board has either none or exactly two
+    UnpairedPortal,
+    /// `(row, col)` lies outside the board
+    OutOfBounds {
+        row: usize,
+        col: usize,
+    },
+    /// A wall can't go on `(row, col)` because an entity stands there
+    Occupied {
+        row: usize,
+        col: usize,
+    },
+    /// Walls cut the goal off from Theseus
+    UnreachableGoal,
+    /// The game is already lost before the first turn, say because Theseus
+    /// starts on a Minotaur
+    StartsLost,
+    /// The board is wider or taller than [`GameConfig::max_dimension`]
+    /// allows
+    TooLarge {
+        width: usize,
+        height: usize,
+    },
+    /// Text given to [`Game::from_json`] isn't a game it wrote
+    InvalidJson,
+    /// Bytes given to [`Game::load_session`] aren't a session it can read
+    InvalidSession,
+    /// A saved session uses a format version this build doesn't know
+    UnknownSessionVersion(u8),
+    /// The board file couldn't be read
+    #[cfg_attr(feature = "serde", serde(skip))]
+    Io(io::ErrorKind),
 }
 impl Display for BoardError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            BoardError::InvalidCharacter(c) => write!(f, "Invalid character: {}", c),
+            BoardError::InvalidCharacter { ch, row, col } => {
+                write!(f, "Invalid character '{}' at row {}, col {}", ch, row, col)
+            }
             BoardError::InvalidSize => write!(f, "Invalid board size"),
+            BoardError::EmptyBoard => write!(f, "Board is empty"),
+            BoardError::EmptyFirstRow => write!(f, "First row of the board is empty"),
+            BoardError::RowLengthMismatch {
+                row,
+                expected,
+                found,
+            } => write!(
+                f,
+                "Row {} is {} wide, but the first row is {} wide",
+                row, found, expected
+            ),
             BoardError::NoMinotaur => write!(f, "No minotaur"),
             BoardError::NoTheseus => write!(f, "No theseus"),
             BoardError::NoGoal => write!(f, "No goal"),
             BoardError::MultipleMinotaur => write!(f, "Multiple minotaur"),
             BoardError::MultipleTheseus => write!(f, "Multiple theseus"),
             BoardError::MultipleGoal => write!(f, "Multiple goal"),
+            BoardError::MultipleKey => write!(f, "Multiple key"),
+            BoardError::MultipleDoor => write!(f, "Multiple door"),
+            BoardError::UnpairedPortal => write!(f, "Portals must come in a pair"),
+            BoardError::OutOfBounds { row, col } => {
+                write!(f, "Row {}, col {} is outside the board", row, col)
+            }
+            BoardError::Occupied { row, col } => {
+                write!(f, "Row {}, col {} is occupied", row, col)
+            }
+            BoardError::UnreachableGoal => write!(f, "Goal is unreachable"),
+            BoardError::StartsLost => write!(f, "Game is lost before it starts"),
+            BoardError::TooLarge { width, height } => {
+                write!(f, "Board is too large: {}x{}", width, height)
+            }
+            BoardError::InvalidJson => write!(f, "Invalid JSON"),
+            BoardError::InvalidSession => write!(f, "Invalid saved session"),
+            BoardError::UnknownSessionVersion(version) => {
+                write!(f, "Unknown saved session version: {}", version)
+            }
+            BoardError::Io(kind) => write!(f, "Could not read board: {}", kind),
         }
     }
 }
 impl Error for BoardError {}
 
-#[derive(Clone)]
+/// A static tile of the board. Entities (Theseus and the Minotaur) are
+/// tracked separately and stand on any tile but `Wall`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Cell {
+    Wall,
+    Empty,
+    Goal,
+    /// Opens the door once Theseus steps on it
+    Key,
+    /// A wall until the key has been picked up
+    Door,
+    /// Sends Theseus to the other portal when he steps on it
+    Portal,
+    /// Theseus keeps sliding across ice until something stops him
+    Ice,
+    /// Kills Theseus if he steps on it
+    Trap,
+    /// Mud, drawn as a digit from 1 to 9: passable, but costs that much to
+    /// end a turn on. See [`Game::solve_min_cost`].
+    Mud(u8),
+}
+
+impl Cell {
+    /// Returns what ending a turn on the tile costs. Only mud costs more
+    /// than 1.
+    pub fn cost(self) -> u32 {
+        match self {
+            Cell::Mud(cost) => u32::from(cost),
+            _ => 1,
+        }
+    }
+}
+
+impl From<Cell> for char {
+    /// Returns the board-file character for the cell
+    fn from(cell: Cell) -> char {
+        match cell {
+            Cell::Wall => 'X',
+            Cell::Empty => ' ',
+            Cell::Goal => 'G',
+            Cell::Key => 'K',
+            Cell::Door => 'D',
+            Cell::Portal => 'P',
+            Cell::Ice => 'I',
+            Cell::Trap => '^',
+            Cell::Mud(cost) => char::from(b'0' + cost),
+        }
+    }
+}
+
+/// Error returned when a character isn't a static tile
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ParseCellError(pub char);
+impl Display for ParseCellError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Not a tile: {}", self.0)
+    }
+}
+impl Error for ParseCellError {}
+
+impl TryFrom<char> for Cell {
+    type Error = ParseCellError;
+
+    fn try_from(ch: char) -> Result<Self, Self::Error> {
+        match ch {
+            'X' => Ok(Cell::Wall),
+            ' ' => Ok(Cell::Empty),
+            'G' => Ok(Cell::Goal),
+            'K' => Ok(Cell::Key),
+            'D' => Ok(Cell::Door),
+            'P' => Ok(Cell::Portal),
+            'I' => Ok(Cell::Ice),
+            '^' => Ok(Cell::Trap),
+            '1'..='9' => Ok(Cell::Mud(ch as u8 - b'0')),
+            other => Err(ParseCellError(other)),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(into = "Vec<String>", try_from = "Vec<String>")
+)]
 pub struct Grid {
     width: usize,
     height: usize,
-    /// Underlying static map: 'X' = wall, ' ' = empty, 'G' = goal
-    cells: Vec<char>,
+    /// Underlying static map, row by row
+    cells: Vec<Cell>,
 }
 
 impl Grid {
-    pub fn new(width: usize, height: usize, cells: Vec<char>) -> Self {
+    pub fn new(width: usize, height: usize, cells: Vec<Cell>) -> Self {
         Self { width, height, cells }
     }
+    pub fn width(&self) -> usize {
+        self.width
+    }
+    pub fn height(&self) -> usize {
+        self.height
+    }
     #[inline]
     fn in_bounds(&self, row: usize, col: usize) -> bool {
         row < self.height && col < self.width
@@ -60,231 +251,3358 @@ impl Grid {
             None
         }
     }
-    pub fn get(&self, row: usize, col: usize) -> Option<char> {
+    pub fn get(&self, row: usize, col: usize) -> Option<Cell> {
         self.idx(row, col).map(|i| self.cells[i])
     }
+    /// Returns the board-file character for the cell, if in bounds
+    pub fn get_char(&self, row: usize, col: usize) -> Option<char> {
+        self.get(row, col).map(char::from)
+    }
     pub fn is_wall(&self, row: usize, col: usize) -> bool {
-        self.get(row, col) == Some('X')
+        self.get(row, col) == Some(Cell::Wall)
     }
     pub fn is_goal(&self, row: usize, col: usize) -> bool {
-        self.get(row, col) == Some('G')
+        self.get(row, col) == Some(Cell::Goal)
     }
     pub fn is_empty(&self, row: usize, col: usize) -> bool {
-        matches!(self.get(row, col), Some(' ') )
+        self.get(row, col) == Some(Cell::Empty)
+    }
+    pub fn is_key(&self, row: usize, col: usize) -> bool {
+        self.get(row, col) == Some(Cell::Key)
+    }
+    pub fn is_door(&self, row: usize, col: usize) -> bool {
+        self.get(row, col) == Some(Cell::Door)
+    }
+    pub fn is_portal(&self, row: usize, col: usize) -> bool {
+        self.get(row, col) == Some(Cell::Portal)
+    }
+    pub fn is_ice(&self, row: usize, col: usize) -> bool {
+        self.get(row, col) == Some(Cell::Ice)
+    }
+    pub fn is_trap(&self, row: usize, col: usize) -> bool {
+        self.get(row, col) == Some(Cell::Trap)
+    }
+    pub fn is_mud(&self, row: usize, col: usize) -> bool {
+        matches!(self.get(row, col), Some(Cell::Mud(_)))
+    }
+    /// Iterates over the cells up, down, left and right of `(row, col)`
+    /// that are on the board and not walls. A grid doesn't wrap; see
+    /// [`Game::wrap`].
+    pub fn neighbors(&self, row: usize, col: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        Command::directions()
+            .filter_map(move |command| Position::new(row, col).step(command))
+            .filter(|to| self.in_bounds(to.row, to.col) && !self.is_wall(to.row, to.col))
+            .map(|to| (to.row, to.col))
+    }
+
+    /// Replaces the tile at `(row, col)` with the one drawn as `cell` in
+    /// board files: any tile [`Cell::try_from`] reads, but not `'T'` or `'M'`,
+    /// which are entities rather than tiles
+    pub fn set(&mut self, row: usize, col: usize, cell: char) -> Result<(), BoardError> {
+        let i = self
+            .idx(row, col)
+            .ok_or(BoardError::OutOfBounds { row, col })?;
+        self.cells[i] = Cell::try_from(cell).map_err(|_| BoardError::InvalidCharacter {
+            ch: cell,
+            row,
+            col,
+        })?;
+        Ok(())
+    }
+}
+
+// Grids convert to and from one string per row, so saved games stay
+// readable.
+impl From<Grid> for Vec<String> {
+    fn from(grid: Grid) -> Self {
+        grid.cells
+            .chunks(grid.width.max(1))
+            .map(|row| row.iter().map(|&cell| char::from(cell)).collect())
+            .collect()
+    }
+}
+
+impl TryFrom<Vec<String>> for Grid {
+    type Error = BoardError;
+
+    fn try_from(rows: Vec<String>) -> Result<Self, Self::Error> {
+        let width = rows.first().map_or(0, |row| row.chars().count());
+        let mut cells = Vec::with_capacity(width * rows.len());
+        for (r, row) in rows.iter().enumerate() {
+            let found = row.chars().count();
+            if found != width {
+                return Err(BoardError::RowLengthMismatch {
+                    row: r,
+                    expected: width,
+                    found,
+                });
+            }
+            for (c, ch) in row.chars().enumerate() {
+                let cell = Cell::try_from(ch).map_err(|_| BoardError::InvalidCharacter {
+                    ch,
+                    row: r,
+                    col: c,
+                })?;
+                cells.push(cell);
+            }
+        }
+        Ok(Grid::new(width, rows.len(), cells))
+    }
+}
+
+/// Parses just the tiles of a board, in the format read by
+/// [`Game::from_board`] but with no entities: `T` and `M` are invalid
+/// characters here. Handy for testing grid logic without a whole game.
+impl FromStr for Grid {
+    type Err = BoardError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lines = Game::board_lines(s);
+        if lines.is_empty() {
+            return Err(BoardError::EmptyBoard);
+        }
+        Grid::try_from(lines.into_iter().map(String::from).collect::<Vec<_>>())
+    }
+}
+
+/// A compact copy of a [`Grid`]'s walls and goals for fast lookups on large
+/// boards: one bit per cell for walls, with the goal cells listed
+/// separately. Answers `is_wall` and `is_goal` exactly as the grid it was
+/// made from does. The solvers look walls up in one while they search.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct BitGrid {
+    width: usize,
+    height: usize,
+    /// Bit `i % 64` of word `i / 64` is set if cell `i`, row by row, is a wall
+    walls: Vec<u64>,
+    goals: Vec<Position>,
+}
+
+impl BitGrid {
+    pub fn width(&self) -> usize {
+        self.width
+    }
+    pub fn height(&self) -> usize {
+        self.height
+    }
+    pub fn is_wall(&self, row: usize, col: usize) -> bool {
+        if row >= self.height || col >= self.width {
+            return false;
+        }
+        let i = row * self.width + col;
+        self.walls[i / 64] & (1 << (i % 64)) != 0
+    }
+    pub fn is_goal(&self, row: usize, col: usize) -> bool {
+        self.goals.contains(&Position::new(row, col))
+    }
+}
+
+impl From<&Grid> for BitGrid {
+    fn from(grid: &Grid) -> Self {
+        let mut walls = vec![0; grid.cells.len().div_ceil(64)];
+        let mut goals = Vec::new();
+        for (i, &cell) in grid.cells.iter().enumerate() {
+            match cell {
+                Cell::Wall => walls[i / 64] |= 1 << (i % 64),
+                Cell::Goal => goals.push(Position::new(i / grid.width, i % grid.width)),
+                _ => {}
+            }
+        }
+        BitGrid {
+            width: grid.width,
+            height: grid.height,
+            walls,
+            goals,
+        }
+    }
+}
+
+/// A cell on the board. Rows count down from the top (y) and columns count
+/// right from the left edge (x), so `(0, 0)` is the top-left corner.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Position {
+    pub row: usize,
+    pub col: usize,
+}
+
+impl Position {
+    pub fn new(row: usize, col: usize) -> Self {
+        Self { row, col }
+    }
+
+    /// Returns the position `dr` rows and `dc` columns away, or `None` if
+    /// that would be above or left of the board
+    pub fn offset(&self, dr: isize, dc: isize) -> Option<Position> {
+        Some(Position {
+            row: self.row.checked_add_signed(dr)?,
+            col: self.col.checked_add_signed(dc)?,
+        })
+    }
+
+    /// Returns the position one `command` away. `Skip` stays in place.
+    pub fn step(&self, command: Command) -> Option<Position> {
+        let (dr, dc) = command.delta();
+        self.offset(dr, dc)
+    }
+}
+
+impl From<(usize, usize)> for Position {
+    fn from((row, col): (usize, usize)) -> Self {
+        Position::new(row, col)
+    }
+}
+
+impl From<Position> for (usize, usize) {
+    fn from(pos: Position) -> Self {
+        (pos.row, pos.col)
+    }
+}
+
+/// Glyph used for walls when rendering a board: the full block `█` (U+2588),
+/// written as an escape so it can't be mangled by a misconfigured editor.
+pub const WALL_GLYPH: char = '\u{2588}';
+
+/// Glyph drawn by [`Game::render_fog`] for tiles Theseus can't see
+pub const FOG_GLYPH: char = '?';
+
+/// Character set used to draw the board
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RenderStyle {
+    /// Walls drawn as [`WALL_GLYPH`], as used by [`Game::show`]
+    #[default]
+    Unicode,
+    /// Plain ASCII, `#` for walls and `.` for empty tiles, for logs and
+    /// terminals without Unicode support
+    Ascii,
+}
+
+impl RenderStyle {
+    /// Returns the glyphs the style draws with
+    pub fn glyphs(self) -> Glyphs {
+        match self {
+            RenderStyle::Unicode => Glyphs::default(),
+            RenderStyle::Ascii => Glyphs {
+                wall: '#',
+                empty: '.',
+                ..Glyphs::default()
+            },
+        }
+    }
+}
+
+/// The character drawn for each kind of tile
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Glyphs {
+    pub wall: char,
+    pub empty: char,
+    pub goal: char,
+    pub theseus: char,
+    pub minotaur: char,
+    pub key: char,
+    pub door: char,
+    pub portal: char,
+    pub ice: char,
+    pub trap: char,
+}
+
+impl Glyphs {
+    /// The characters of the board file format
+    pub const BOARD: Glyphs = Glyphs {
+        wall: 'X',
+        empty: ' ',
+        goal: 'G',
+        theseus: 'T',
+        minotaur: 'M',
+        key: 'K',
+        door: 'D',
+        portal: 'P',
+        ice: 'I',
+        trap: '^',
+    };
+}
+
+impl Default for Glyphs {
+    /// The glyphs used by [`Game::show`]
+    fn default() -> Self {
+        Glyphs {
+            wall: WALL_GLYPH,
+            ..Glyphs::BOARD
+        }
+    }
+}
+
+/// ANSI SGR code that clears all colors
+pub const ANSI_RESET: &str = "\x1b[0m";
+
+/// What a cell of the board shows, as returned by [`Game::cell_at`]: the
+/// entity standing there if any, otherwise the tile
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CellView {
+    Theseus,
+    Minotaur,
+    Wall,
+    Goal,
+    /// The key, until Theseus picks it up
+    Key,
+    /// The door, while it's locked
+    Door,
+    Portal,
+    Ice,
+    Trap,
+    /// Mud of the given cost
+    Mud(u8),
+    /// Nothing, including a picked-up key or an unlocked door
+    Empty,
+}
+
+impl CellView {
+    fn glyph(self, glyphs: &Glyphs) -> char {
+        match self {
+            CellView::Theseus => glyphs.theseus,
+            CellView::Minotaur => glyphs.minotaur,
+            CellView::Wall => glyphs.wall,
+            CellView::Goal => glyphs.goal,
+            CellView::Key => glyphs.key,
+            CellView::Door => glyphs.door,
+            CellView::Portal => glyphs.portal,
+            CellView::Ice => glyphs.ice,
+            CellView::Trap => glyphs.trap,
+            CellView::Mud(cost) => char::from(Cell::Mud(cost)),
+            CellView::Empty => glyphs.empty,
+        }
+    }
+
+    /// ANSI SGR code the cell is drawn in, if any
+    fn color(self) -> Option<&'static str> {
+        match self {
+            CellView::Theseus => Some("\x1b[36m"),
+            CellView::Minotaur => Some("\x1b[31m"),
+            CellView::Wall => Some("\x1b[90m"),
+            CellView::Goal => Some("\x1b[32m"),
+            CellView::Key => Some("\x1b[33m"),
+            CellView::Door => Some("\x1b[35m"),
+            CellView::Portal => Some("\x1b[34m"),
+            CellView::Ice => Some("\x1b[96m"),
+            CellView::Trap => Some("\x1b[91m"),
+            CellView::Mud(_) => Some("\x1b[2;33m"),
+            CellView::Empty => None,
+        }
+    }
+}
+
+/// Rules a game is played by, chosen when the board is loaded with
+/// [`Game::from_board_with_config`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GameConfig {
+    /// How many times the Minotaurs move per turn. Defaults to 1.
+    pub minotaur_speed: u32,
+    /// Turns allowed to reach the goal, if limited. Defaults to no limit.
+    pub max_turns: Option<u32>,
+    /// Whether a board may have more than one Minotaur. Defaults to true.
+    pub allow_multiple_minotaurs: bool,
+    /// Whether a board may have more than one goal, in which case Theseus
+    /// wins by visiting all of them. Defaults to false.
+    pub allow_multiple_goals: bool,
+    /// Which way the Minotaur closes the gap to Theseus first
+    pub minotaur_axis_priority: Axis,
+    /// Whether to reject boards where Theseus can't walk to the goal even
+    /// with no Minotaur around. Defaults to false.
+    pub require_reachable_goal: bool,
+    /// Whether to reject games that are lost before the first turn, which
+    /// is almost always a mistake in the level. Defaults to false.
+    pub reject_lost_start: bool,
+    /// Whether walking off one edge of the board comes back on the opposite
+    /// edge. Defaults to false.
+    pub wrap: bool,
+    /// Whether Theseus may move diagonally with [`Command::diagonals`].
+    /// Defaults to false.
+    pub allow_diagonal_moves: bool,
+    /// The most rows or columns a board may have. Defaults to
+    /// [`GameConfig::DEFAULT_MAX_DIMENSION`].
+    pub max_dimension: usize,
+}
+
+impl GameConfig {
+    /// Far beyond any hand-made board, but keeps a submitted board from
+    /// swamping the solver
+    pub const DEFAULT_MAX_DIMENSION: usize = 1024;
+}
+
+impl Default for GameConfig {
+    fn default() -> Self {
+        GameConfig {
+            minotaur_speed: 1,
+            max_turns: None,
+            allow_multiple_minotaurs: true,
+            allow_multiple_goals: false,
+            minotaur_axis_priority: Axis::default(),
+            require_reachable_goal: false,
+            reject_lost_start: false,
+            wrap: false,
+            allow_diagonal_moves: false,
+            max_dimension: GameConfig::DEFAULT_MAX_DIMENSION,
+        }
     }
 }
 
+/// The order in which the Minotaur tries to close the gap to Theseus
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Axis {
+    /// Columns first, then rows: the classic rule
+    #[default]
+    HorizontalFirst,
+    /// Rows first, then columns
+    VerticalFirst,
+}
+
+/// What happened in a turn played with [`Game::step`], as passed to the
+/// callback set with [`Game::set_on_turn`] and kept in [`Game::trace`]
+#[derive(Clone, Debug, PartialEq)]
+pub struct TurnEvent {
+    /// The command Theseus was given
+    pub command: Command,
+    /// Where Theseus ended the turn
+    pub theseus: Position,
+    /// Where every Minotaur ended the turn, in board order
+    pub minotaurs: Vec<Position>,
+    /// The status after the turn
+    pub status: GameStatus,
+}
+
+type TurnCallback = dyn FnMut(&TurnEvent) + Send;
+
+/// What happened in a turn played with [`Game::apply`]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TurnOutcome {
+    /// Whether Theseus's move went through. Always true for `Skip`.
+    pub theseus_moved: bool,
+    /// Whether any Minotaur ended the turn somewhere else
+    pub minotaur_moved: bool,
+    /// The status after the turn
+    pub status: GameStatus,
+}
+
 #[derive(Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "GameData")
+)]
 pub struct Game {
     grid: Grid,
-    theseus_row: usize,
-    theseus_col: usize,
-    minotaur_row: usize,
-    minotaur_col: usize,
-    goal_row: usize,
-    goal_col: usize,
+    theseus: Position,
+    /// Minotaur positions, in board order. Never empty.
+    minotaurs: Vec<Position>,
+    goal: Position,
+    /// Whether Theseus has to visit every goal to win, rather than one
+    multiple_goals: bool,
+    /// Goals Theseus still has to visit, in board order. Only used with
+    /// `multiple_goals`.
+    goals_left: Vec<Position>,
+    /// Whether Theseus has picked up the key, which opens the door
+    has_key: bool,
+    /// The two portals, if the board has them
+    portals: Option<(Position, Position)>,
+    /// Number of Minotaur moves per turn
+    minotaur_speed: u32,
+    /// Which way the Minotaur closes the gap to Theseus first
+    minotaur_axis_priority: Axis,
+    /// Whether the edges of the board wrap around
+    wrap: bool,
+    /// Whether Theseus may move diagonally
+    diagonal_moves: bool,
+    /// Turns played so far
+    turn: u32,
+    /// Turns allowed to reach the goal, if limited
+    max_turns: Option<u32>,
+    /// How the Minotaurs pick their moves
+    #[cfg_attr(feature = "serde", serde(skip))]
+    minotaur_policy: Arc<dyn MinotaurPolicy>,
+    /// How the goal moves at the end of each turn
+    #[cfg_attr(feature = "serde", serde(skip))]
+    goal_policy: Arc<dyn GoalPolicy>,
+    /// Called after every `step`
+    #[cfg_attr(feature = "serde", serde(skip))]
+    on_turn: Option<Arc<Mutex<TurnCallback>>>,
+    /// Every turn played with `step` since tracing was turned on, if it is
+    #[cfg_attr(feature = "serde", serde(skip))]
+    trace: Option<Vec<TurnEvent>>,
+    /// Where everything stood when the board was loaded
+    initial: State,
+    /// Every cell Theseus has ended a move on, including where he started
+    visited: HashSet<(usize, usize)>,
+    /// Entity positions from before each turn, most recent last, with the
+    /// cell the turn added to `visited`, if any
+    #[cfg_attr(feature = "serde", serde(skip))]
+    undo_stack: Vec<(State, Option<(usize, usize)>)>,
+    /// Turns taken back by `undo`, most recently undone last
+    #[cfg_attr(feature = "serde", serde(skip))]
+    redo_stack: Vec<State>,
+    /// A packed copy of the walls, set on the copies the solvers search
+    /// with. They never edit the grid, so it can't go stale.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    walls: Option<Arc<BitGrid>>,
 }
 
 impl Game {
     pub fn from_board(board: &str) -> Result<Game, BoardError> {
-        // Read lines, filter out empty trailing lines
-        let lines: Vec<&str> = board.lines().collect();
+        Game::from_board_with_config(board, GameConfig::default())
+    }
+
+    /// Like [`Game::from_board`], but rejects boards with more than one
+    /// Minotaur with `BoardError::MultipleMinotaur`.
+    pub fn from_board_strict(board: &str) -> Result<Game, BoardError> {
+        let config = GameConfig {
+            allow_multiple_minotaurs: false,
+            ..GameConfig::default()
+        };
+        Game::from_board_with_config(board, config)
+    }
+
+    /// Like [`Game::from_board`], for hand-written board files with notes
+    /// in them: lines starting with `#` are comments and empty lines are
+    /// ignored, so neither counts towards the board's size. A line of
+    /// spaces is still a row of empty tiles.
+    pub fn from_annotated_board(board: &str) -> Result<Game, BoardError> {
+        let cleaned: String = board
+            .lines()
+            .map(|line| line.strip_suffix('\r').unwrap_or(line))
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .flat_map(|line| line.chars().chain(std::iter::once('\n')))
+            .collect();
+        Game::from_board(&cleaned)
+    }
+
+    /// Checks a board for [`Game::from_board`] and reports every problem
+    /// with it, rather than stopping at the first. Each bad character and
+    /// each line of the wrong length is reported; the others at most once.
+    /// Empty if the board loads.
+    pub fn validate(board: &str) -> Vec<BoardError> {
+        let lines = Game::board_lines(board);
         if lines.is_empty() {
-            return Err(BoardError::InvalidSize);
+            return vec![BoardError::EmptyBoard];
         }
+        let mut errors = Vec::new();
         let width = lines[0].chars().count();
         if width == 0 {
-            return Err(BoardError::InvalidSize);
+            errors.push(BoardError::EmptyFirstRow);
         }
-        let height = lines.len();
-        let mut cells: Vec<char> = Vec::with_capacity(width * height);
-
-        // Track entities
-        let mut t_pos: Option<(usize, usize)> = None;
-        let mut m_pos: Option<(usize, usize)> = None;
-        let mut g_pos: Option<(usize, usize)> = None;
 
+        let (mut theseus, mut minotaurs, mut goals) = (0, 0, 0);
+        let (mut keys, mut doors, mut portals) = (0, 0, 0);
         for (r, line) in lines.iter().enumerate() {
-            if line.chars().count() != width {
-                return Err(BoardError::InvalidSize);
+            let found = line.chars().count();
+            if width > 0 && found != width {
+                errors.push(BoardError::RowLengthMismatch {
+                    row: r,
+                    expected: width,
+                    found,
+                });
             }
             for (c, ch) in line.chars().enumerate() {
                 match ch {
-                    'X' | ' ' | 'G' | 'T' | 'M' => {
-                        // For the static grid, store 'X', ' ', or 'G'.
-                        match ch {
-                            'X' => cells.push('X'),
-                            'G' => {
-                                if g_pos.is_some() { return Err(BoardError::MultipleGoal); }
-                                g_pos = Some((r, c));
-                                cells.push('G');
-                            }
-                            'T' => {
-                                if t_pos.is_some() { return Err(BoardError::MultipleTheseus); }
-                                t_pos = Some((r, c));
-                                cells.push(' ');
-                            }
-                            'M' => {
-                                if m_pos.is_some() { return Err(BoardError::MultipleMinotaur); }
-                                m_pos = Some((r, c));
-                                cells.push(' ');
-                            }
-                            ' ' => cells.push(' '),
-                            _ => unreachable!(),
-                        }
-                    }
-                    other => return Err(BoardError::InvalidCharacter(other)),
+                    'T' => theseus += 1,
+                    'M' => minotaurs += 1,
+                    _ => match Cell::try_from(ch) {
+                        Ok(Cell::Goal) => goals += 1,
+                        Ok(Cell::Key) => keys += 1,
+                        Ok(Cell::Door) => doors += 1,
+                        Ok(Cell::Portal) => portals += 1,
+                        Ok(_) => {}
+                        Err(_) => errors.push(BoardError::InvalidCharacter { ch, row: r, col: c }),
+                    },
                 }
             }
         }
+        let (max, height) = (GameConfig::DEFAULT_MAX_DIMENSION, lines.len());
+        if width > max || height > max {
+            errors.push(BoardError::TooLarge { width, height });
+        }
 
-        let (tr, tc) = t_pos.ok_or(BoardError::NoTheseus)?;
-        let (mr, mc) = m_pos.ok_or(BoardError::NoMinotaur)?;
-        let (gr, gc) = g_pos.ok_or(BoardError::NoGoal)?;
+        match theseus {
+            0 => errors.push(BoardError::NoTheseus),
+            1 => {}
+            _ => errors.push(BoardError::MultipleTheseus),
+        }
+        if minotaurs == 0 {
+            errors.push(BoardError::NoMinotaur);
+        }
+        match goals {
+            0 => errors.push(BoardError::NoGoal),
+            1 => {}
+            _ => errors.push(BoardError::MultipleGoal),
+        }
+        if keys > 1 {
+            errors.push(BoardError::MultipleKey);
+        }
+        if doors > 1 {
+            errors.push(BoardError::MultipleDoor);
+        }
+        if portals != 0 && portals != 2 {
+            errors.push(BoardError::UnpairedPortal);
+        }
+        errors
+    }
 
-        let grid = Grid::new(width, height, cells);
+    /// Like [`Game::from_board`], with the rules set by `config` instead of
+    /// the defaults
+    pub fn from_board_with_config(board: &str, config: GameConfig) -> Result<Game, BoardError> {
+        Game::parse(board, &config, &Glyphs::BOARD)
+    }
 
-        Ok(Game {
-            grid,
-            theseus_row: tr,
-            theseus_col: tc,
-            minotaur_row: mr,
-            minotaur_col: mc,
-            goal_row: gr,
-            goal_col: gc,
-        })
+    /// Like [`Game::from_board`], for boards drawn with other characters,
+    /// e.g. `#` for walls and `.` for floors. Also reads back anything
+    /// written by [`Game::render_with_glyphs`].
+    pub fn from_board_with(board: &str, glyphs: &Glyphs) -> Result<Game, BoardError> {
+        Game::parse(board, &GameConfig::default(), glyphs)
     }
 
-    pub fn show(&self) {
-        for r in 0..self.grid.height {
-            let mut line = String::with_capacity(self.grid.width);
-            for c in 0..self.grid.width {
-                if self.theseus_row == r && self.theseus_col == c {
-                    line.push('T');
-                } else if self.minotaur_row == r && self.minotaur_col == c {
-                    line.push('M');
-                } else if self.grid.is_wall(r, c) {
-                    // Draw a block for walls
-                    line.push('█');
-                } else if self.grid.is_goal(r, c) {
-                    line.push('G');
-                } else {
-                    line.push(' ');
-                }
-            }
-            println!("{}", line);
+    /// Splits a board into its lines, dropping empty trailing lines
+    fn board_lines(board: &str) -> Vec<&str> {
+        // `lines` already handles "\r\n", but a lone '\r' can still end the
+        // last line.
+        let mut lines: Vec<&str> = board
+            .lines()
+            .map(|line| line.strip_suffix('\r').unwrap_or(line))
+            .collect();
+        while lines.last() == Some(&"") {
+            lines.pop();
         }
+        lines
     }
 
-    pub fn minotaur_move(&mut self) {
-        // Helper to test if move to (r,c) is valid (within bounds and not a wall)
-        let try_move = |r: isize, c: isize| -> Option<(usize, usize)> {
-            if r < 0 || c < 0 { return None; }
-            let (r, c) = (r as usize, c as usize);
-            if self.grid.in_bounds(r, c) && !self.grid.is_wall(r, c) {
-                Some((r, c))
-            } else {
-                None
-            }
-        };
+    fn parse(board: &str, config: &GameConfig, glyphs: &Glyphs) -> Result<Game, BoardError> {
+        let lines = Game::board_lines(board);
+        if lines.is_empty() {
+            return Err(BoardError::EmptyBoard);
+        }
+        let width = lines[0].chars().count();
+        if width == 0 {
+            return Err(BoardError::EmptyFirstRow);
+        }
+        let height = lines.len();
+        if width > config.max_dimension || height > config.max_dimension {
+            return Err(BoardError::TooLarge { width, height });
+        }
+        let mut cells: Vec<Cell> = Vec::with_capacity(width * height);
 
-        let tx = self.theseus_col as isize;
-        let ty = self.theseus_row as isize;
-        let mx = self.minotaur_col as isize;
-        let my = self.minotaur_row as isize;
+        // Track entities
+        let mut t_pos: Option<Position> = None;
+        let mut minotaurs: Vec<Position> = Vec::new();
+        let mut goals: Vec<Position> = Vec::new();
+        let mut key_seen = false;
+        let mut door_seen = false;
+        let mut portals: Vec<Position> = Vec::new();
 
-        // 1) Try horizontal move that decreases |tx - mx|
-        if tx < mx {
-            if let Some((nr, nc)) = try_move(my, mx - 1) {
-                self.minotaur_row = nr;
-                self.minotaur_col = nc;
-                return;
+        for (r, line) in lines.iter().enumerate() {
+            let found = line.chars().count();
+            if found != width {
+                return Err(BoardError::RowLengthMismatch {
+                    row: r,
+                    expected: width,
+                    found,
+                });
             }
-        } else if tx > mx {
-            if let Some((nr, nc)) = try_move(my, mx + 1) {
-                self.minotaur_row = nr;
-                self.minotaur_col = nc;
-                return;
+            for (c, ch) in line.chars().enumerate() {
+                // Entities stand on empty tiles in the static grid.
+                match ch {
+                    _ if ch == glyphs.theseus => {
+                        if t_pos.is_some() { return Err(BoardError::MultipleTheseus); }
+                        t_pos = Some(Position::new(r, c));
+                        cells.push(Cell::Empty);
+                    }
+                    _ if ch == glyphs.minotaur => {
+                        if !config.allow_multiple_minotaurs && !minotaurs.is_empty() {
+                            return Err(BoardError::MultipleMinotaur);
+                        }
+                        minotaurs.push(Position::new(r, c));
+                        cells.push(Cell::Empty);
+                    }
+                    _ if ch == glyphs.goal => {
+                        if !config.allow_multiple_goals && !goals.is_empty() {
+                            return Err(BoardError::MultipleGoal);
+                        }
+                        goals.push(Position::new(r, c));
+                        cells.push(Cell::Goal);
+                    }
+                    _ if ch == glyphs.key => {
+                        if key_seen {
+                            return Err(BoardError::MultipleKey);
+                        }
+                        key_seen = true;
+                        cells.push(Cell::Key);
+                    }
+                    _ if ch == glyphs.door => {
+                        if door_seen {
+                            return Err(BoardError::MultipleDoor);
+                        }
+                        door_seen = true;
+                        cells.push(Cell::Door);
+                    }
+                    _ if ch == glyphs.portal => {
+                        portals.push(Position::new(r, c));
+                        cells.push(Cell::Portal);
+                    }
+                    _ if ch == glyphs.ice => cells.push(Cell::Ice),
+                    _ if ch == glyphs.trap => cells.push(Cell::Trap),
+                    _ if ch == glyphs.wall => cells.push(Cell::Wall),
+                    _ if ch == glyphs.empty => cells.push(Cell::Empty),
+                    '1'..='9' => cells.push(Cell::Mud(ch as u8 - b'0')),
+                    _ => {
+                        return Err(BoardError::InvalidCharacter { ch, row: r, col: c });
+                    }
+                }
             }
         }
 
-        // 2) Otherwise, try vertical move that decreases |ty - my|
-        if ty < my {
-            if let Some((nr, nc)) = try_move(my - 1, mx) {
-                self.minotaur_row = nr;
-                self.minotaur_col = nc;
-                return;
-            }
-        } else if ty > my {
-            if let Some((nr, nc)) = try_move(my + 1, mx) {
-                self.minotaur_row = nr;
-                self.minotaur_col = nc;
-                return;
-            }
+        let theseus = t_pos.ok_or(BoardError::NoTheseus)?;
+        if minotaurs.is_empty() {
+            return Err(BoardError::NoMinotaur);
         }
-        // 3) Else: don't move
+        if goals.is_empty() {
+            return Err(BoardError::NoGoal);
+        }
+        let portals = match portals[..] {
+            [] => None,
+            [a, b] => Some((a, b)),
+            _ => return Err(BoardError::UnpairedPortal),
+        };
+
+        let grid = Grid::new(width, height, cells);
+        Game::assemble(grid, theseus, minotaurs, goals, portals, false, config)
     }
 
-    pub fn theseus_move(&mut self, command: Command) {
-        let (dr, dc) = match command {
-            Command::Up => (-1, 0),
-            Command::Down => (1, 0),
-            Command::Left => (0, -1),
-            Command::Right => (0, 1),
-            Command::Skip => (0, 0),
+    /// Puts a game together from parts that have already been checked.
+    /// `goals` holds at least one goal, and only one unless the config
+    /// allows more.
+    fn assemble(
+        grid: Grid,
+        theseus: Position,
+        minotaurs: Vec<Position>,
+        goals: Vec<Position>,
+        portals: Option<(Position, Position)>,
+        has_key: bool,
+        config: &GameConfig,
+    ) -> Result<Game, BoardError> {
+        let goal = goals[0];
+        let goals_left = if config.allow_multiple_goals {
+            goals
+        } else {
+            Vec::new()
+        };
+        let initial = State {
+            theseus,
+            minotaurs: minotaurs.clone(),
+            has_key,
+            goal,
+            goals_left: goals_left.clone(),
         };
 
-        let new_r = self.theseus_row as isize + dr;
-        let new_c = self.theseus_col as isize + dc;
-        if new_r < 0 || new_c < 0 {
-            return;
+        let game = Game {
+            grid,
+            theseus,
+            minotaurs,
+            goal,
+            multiple_goals: config.allow_multiple_goals,
+            goals_left,
+            has_key,
+            portals,
+            minotaur_speed: config.minotaur_speed,
+            minotaur_axis_priority: config.minotaur_axis_priority,
+            wrap: config.wrap,
+            diagonal_moves: config.allow_diagonal_moves,
+            turn: 0,
+            max_turns: config.max_turns,
+            minotaur_policy: default_policy(),
+            goal_policy: default_goal_policy(),
+            on_turn: None,
+            trace: None,
+            initial,
+            visited: HashSet::from([(theseus.row, theseus.col)]),
+            undo_stack: Vec::new(),
+            redo_stack: Vec::new(),
+            walls: None,
+        };
+        if config.require_reachable_goal {
+            let reachable = game.reachable_cells();
+            let mut goals = std::iter::once(&goal).chain(&game.goals_left);
+            if !goals.all(|goal| reachable.contains(&(goal.row, goal.col))) {
+                return Err(BoardError::UnreachableGoal);
+            }
         }
-        let (nr, nc) = (new_r as usize, new_c as usize);
-        if self.grid.in_bounds(nr, nc) && !self.grid.is_wall(nr, nc) {
-            self.theseus_row = nr;
-            self.theseus_col = nc;
+        if config.reject_lost_start && game.status() == GameStatus::Lose {
+            return Err(BoardError::StartsLost);
         }
+        Ok(game)
     }
 
-    pub fn status(&self) -> GameStatus {
-        if self.theseus_row == self.minotaur_row && self.theseus_col == self.minotaur_col {
-            return GameStatus::Lose;
+    /// Reads a board file and parses it with [`Game::from_board`]
+    #[cfg(feature = "std-io")]
+    pub fn from_file<P: AsRef<std::path::Path>>(path: P) -> Result<Game, BoardError> {
+        let board = std::fs::read_to_string(path).map_err(|e| BoardError::Io(e.kind()))?;
+        Game::from_board(&board)
+    }
+
+    /// Like [`Game::from_board`], but for a board of a known `width` and
+    /// `height`. Short lines are padded with empty tiles, and missing rows are
+    /// filled with empty rows. Errors if any line is longer than `width` or
+    /// there are more than `height` rows, and with `TooLarge` if `width` or
+    /// `height` is over [`GameConfig::DEFAULT_MAX_DIMENSION`].
+    pub fn from_board_sized(board: &str, width: usize, height: usize) -> Result<Game, BoardError> {
+        let max = GameConfig::DEFAULT_MAX_DIMENSION;
+        if width > max || height > max {
+            return Err(BoardError::TooLarge { width, height });
         }
-        if self.theseus_row == self.goal_row && self.theseus_col == self.goal_col {
-            return GameStatus::Win;
+        let lines: Vec<&str> = board.lines().collect();
+        if lines.len() > height {
+            return Err(BoardError::InvalidSize);
         }
-        GameStatus::Continue
+        let mut padded = String::with_capacity((width + 1) * height);
+        for r in 0..height {
+            let line = lines.get(r).copied().unwrap_or("");
+            let len = line.chars().count();
+            if len > width {
+                return Err(BoardError::InvalidSize);
+            }
+            padded.push_str(line);
+            padded.extend(std::iter::repeat_n(' ', width - len));
+            padded.push('\n');
+        }
+        Game::from_board(&padded)
     }
-}
 
-// Derived queries the autograder expects
-impl Game {
-    /// Returns true if the given position is Theseus
-    pub fn is_theseus(&self, row: usize, col: usize) -> bool {
-        self.theseus_row == row && self.theseus_col == col
+    #[cfg(feature = "std-io")]
+    pub fn show(&self) {
+        // Matches `println!`, which also panics if stdout is gone.
+        self.show_to(&mut io::stdout().lock())
+            .expect("failed printing to stdout");
     }
-    /// Returns true if the given position is Minotaur
-    pub fn is_minotaur(&self, row: usize, col: usize) -> bool {
-        self.minotaur_row == row && self.minotaur_col == col
+
+    /// Writes the same rendering as [`Game::show`] to `w`.
+    pub fn show_to<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        write!(w, "{}", self)
     }
-    /// Returns true if the given position is a wall
-    pub fn is_wall(&self, row: usize, col: usize) -> bool {
-        self.grid.is_wall(row, col)
+
+    /// Renders the board as a string, one line per row, each ending in '\n'.
+    pub fn render(&self) -> String {
+        self.render_with(RenderStyle::Unicode)
     }
-    /// Returns true if the given position is the goal
-    pub fn is_goal(&self, row: usize, col: usize) -> bool {
-        self.grid.is_goal(row, col)
+
+    /// Like [`Game::render`], in the given style
+    pub fn render_with(&self, style: RenderStyle) -> String {
+        self.render_with_glyphs(&style.glyphs())
     }
-    /// Returns true if the given position is empty
-    pub fn is_empty(&self, row: usize, col: usize) -> bool {
-        !self.is_theseus(row, col)
-            && !self.is_minotaur(row, col)
-            && !self.is_wall(row, col)
-            && !self.is_goal(row, col)
+
+    /// Like [`Game::render`], drawing each tile with the given glyph.
+    /// Entities are drawn over the tile they stand on.
+    pub fn render_with_glyphs(&self, glyphs: &Glyphs) -> String {
+        let mut out = String::with_capacity((self.grid.width + 1) * self.grid.height);
+        for r in 0..self.grid.height {
+            for c in 0..self.grid.width {
+                out.push(self.view(r, c).glyph(glyphs));
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Like [`Game::render`], with each entity and tile wrapped in ANSI color
+    /// codes. Every colored cell is followed by a reset, so a line never
+    /// leaks its color into the next one.
+    ///
+    /// Only meant for terminals; see [`Game::show`] for plain output.
+    pub fn render_ansi(&self) -> String {
+        let glyphs = Glyphs::default();
+        let mut out = String::new();
+        for r in 0..self.grid.height {
+            for c in 0..self.grid.width {
+                let view = self.view(r, c);
+                match view.color() {
+                    Some(color) => {
+                        out.push_str(color);
+                        out.push(view.glyph(&glyphs));
+                        out.push_str(ANSI_RESET);
+                    }
+                    None => out.push(view.glyph(&glyphs)),
+                }
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Like [`Game::render`], but only tiles within `radius` of Theseus, by
+    /// Chebyshev distance, are shown. Everything further away, Minotaurs
+    /// included, is drawn as [`FOG_GLYPH`].
+    pub fn render_fog(&self, radius: usize) -> String {
+        let glyphs = Glyphs::default();
+        let mut out = String::with_capacity((self.grid.width + 1) * self.grid.height);
+        for r in 0..self.grid.height {
+            for c in 0..self.grid.width {
+                if chebyshev(self.theseus, Position::new(r, c)) <= radius {
+                    out.push(self.view(r, c).glyph(&glyphs));
+                } else {
+                    out.push(FOG_GLYPH);
+                }
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Returns the cells that [`Game::render`] draws differently now than
+    /// it drew `previous`, with their new character, row by row. Lets a
+    /// terminal redraw only what moved. If the boards differ in size, every
+    /// cell outside `previous` counts as changed.
+    pub fn render_diff(&self, previous: &Game) -> Vec<(usize, usize, char)> {
+        let glyphs = Glyphs::default();
+        self.positions()
+            .filter_map(|(row, col)| {
+                let ch = self.view(row, col).glyph(&glyphs);
+                let before = previous.cell_at(row, col).map(|view| view.glyph(&glyphs));
+                (before != Some(ch)).then_some((row, col, ch))
+            })
+            .collect()
+    }
+
+    /// Classifies the cell at `(row, col)`: Theseus, then a Minotaur, then
+    /// the tile underneath. `None` if it's off the board.
+    pub fn cell_at(&self, row: usize, col: usize) -> Option<CellView> {
+        self.grid.in_bounds(row, col).then(|| self.view(row, col))
+    }
+
+    /// Like [`Game::cell_at`], treating cells off the board as empty
+    fn view(&self, row: usize, col: usize) -> CellView {
+        if self.is_theseus(row, col) {
+            CellView::Theseus
+        } else if self.is_minotaur(row, col) {
+            CellView::Minotaur
+        } else if self.grid.is_wall(row, col) {
+            CellView::Wall
+        } else if self.is_goal(row, col) {
+            CellView::Goal
+        } else if self.is_key(row, col) {
+            CellView::Key
+        } else if self.is_door(row, col) {
+            CellView::Door
+        } else if self.grid.is_portal(row, col) {
+            CellView::Portal
+        } else if self.grid.is_ice(row, col) {
+            CellView::Ice
+        } else if self.grid.is_trap(row, col) {
+            CellView::Trap
+        } else if let Some(Cell::Mud(cost)) = self.grid.get(row, col) {
+            CellView::Mud(cost)
+        } else {
+            CellView::Empty
+        }
+    }
+
+    /// Writes the current state back out in the text format read by
+    /// [`Game::from_board`].
+    ///
+    /// Entities take precedence over the tiles they stand on, Theseus over the
+    /// Minotaur. So if either is standing on the goal, or entities share a
+    /// cell, the hidden characters are dropped: `from_board` will reject the
+    /// result (`NoGoal` or `NoMinotaur`) or see fewer Minotaurs. Otherwise the
+    /// round trip is exact.
+    pub fn to_board(&self) -> String {
+        self.render_with_glyphs(&Glyphs::BOARD)
+    }
+
+    /// Moves every Minotaur one step towards Theseus, in board order.
+    /// Returns whether any of them moved. Theseus is caught the moment a
+    /// Minotaur enters his cell, and the rest then stay where they are.
+    pub fn minotaur_move(&mut self) -> bool {
+        let mut moved = false;
+        for i in 0..self.minotaurs.len() {
+            if self.status() == GameStatus::Lose {
+                break;
+            }
+            let command = self.next_move_for(self.minotaurs[i]);
+            if command == Command::Skip {
+                continue;
+            }
+            if let Some(next) = self.move_target(self.minotaurs[i], command) {
+                self.minotaurs[i] = next;
+                moved = true;
+            }
+        }
+        moved
+    }
+
+    /// Returns the step [`Game::minotaur_move`] would take the Minotaur (the
+    /// first one, if there are several), or `Skip` if it would stay put.
+    /// Nothing is moved.
+    pub fn minotaur_next_move(&self) -> Command {
+        self.next_move_for(self.minotaurs[0])
+    }
+
+    /// Asks the policy where the Minotaur at `minotaur` goes, turning moves
+    /// it can't make into `Skip`
+    fn next_move_for(&self, minotaur: Position) -> Command {
+        let command = self.minotaur_policy.choose(self, minotaur);
+        match self.move_target(minotaur, command) {
+            Some(_) => command,
+            None => Command::Skip,
+        }
+    }
+
+    /// Sets how the Minotaurs pick their moves. Boards start with
+    /// [`GreedyPolicy`].
+    pub fn set_minotaur_policy<P: MinotaurPolicy + 'static>(&mut self, policy: P) {
+        self.minotaur_policy = Arc::new(policy);
+    }
+
+    /// Sets how the goal moves at the end of each turn. Boards start with
+    /// [`StationaryGoal`].
+    pub fn set_goal_policy<P: GoalPolicy + 'static>(&mut self, policy: P) {
+        self.goal_policy = Arc::new(policy);
+    }
+
+    /// Moves the goal one step onto an empty tile. Entities don't block it,
+    /// so the goal moving onto Theseus wins the game. Returns whether it
+    /// moved; it stays put if the tile isn't empty, or if the board has
+    /// several goals.
+    pub fn move_goal(&mut self, command: Command) -> bool {
+        if self.multiple_goals {
+            return false;
+        }
+        match self.neighbor(self.goal, command) {
+            Some(to) if command != Command::Skip && self.grid.is_empty(to.row, to.col) => {
+                self.place_goal(to);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn place_goal(&mut self, to: Position) {
+        let from = self.goal;
+        self.grid.cells[from.row * self.grid.width + from.col] = Cell::Empty;
+        self.grid.cells[to.row * self.grid.width + to.col] = Cell::Goal;
+        self.goal = to;
+    }
+
+    /// Returns the cell `command` leads to from `from`, or `None` if that is
+    /// off the board, a wall, or a locked door. `Skip` leads to `from` itself.
+    /// On a wrapping board nothing is off the board.
+    pub fn move_target(&self, from: Position, command: Command) -> Option<Position> {
+        let to = self.neighbor(from, command)?;
+        let is_wall = match &self.walls {
+            Some(walls) => walls.is_wall(to.row, to.col),
+            None => self.grid.is_wall(to.row, to.col),
+        };
+        if self.grid.in_bounds(to.row, to.col) && !is_wall && !self.is_door(to.row, to.col) {
+            Some(to)
+        } else {
+            None
+        }
+    }
+
+    /// Moves Theseus and starts a new turn in the undo history. Minotaur
+    /// moves that follow are undone together with it.
+    ///
+    /// Returns false if a wall or the edge of the board is in the way, in
+    /// which case Theseus stays put but the turn still counts. `Skip` always
+    /// succeeds. Diagonal moves only check the cell they land on, so they
+    /// can squeeze between two walls touching at a corner.
+    pub fn theseus_move(&mut self, command: Command) -> bool {
+        self.start_turn();
+        let moved = self.apply_theseus_move(command);
+        self.mark_visited();
+        moved
+    }
+
+    /// Returns the commands Theseus can play from where he stands without
+    /// walking into a wall or off the board, in [`Command::all`] order and
+    /// then the diagonals if they're allowed. `Skip` is always included.
+    pub fn legal_theseus_moves(&self) -> Vec<Command> {
+        self.theseus_commands()
+            .filter(|&command| self.move_target(self.theseus, command).is_some())
+            .collect()
+    }
+
+    /// Moves Theseus one step. Stepping onto a portal takes him straight to
+    /// the other one, even if a Minotaur is waiting there, unless a Minotaur
+    /// is standing on the portal he steps on. On ice he keeps sliding the
+    /// same way until he reaches another tile, is blocked, or runs into a
+    /// Minotaur. Minotaurs use neither portals nor ice, and walk over them
+    /// like empty tiles. Returns false if Theseus couldn't move at all.
+    fn apply_theseus_move(&mut self, command: Command) -> bool {
+        if command == Command::Skip {
+            return true;
+        }
+        if !self.theseus_can_move(command) {
+            return false;
+        }
+        let start = self.theseus;
+        while let Some(to) = self.move_target(self.theseus, command) {
+            self.theseus = to;
+            if self.grid.is_key(to.row, to.col) {
+                self.has_key = true;
+            }
+            if self.minotaurs.contains(&to) {
+                break;
+            }
+            if let Some(exit) = self.portal_exit(to) {
+                self.theseus = exit;
+                break;
+            }
+            // On a wrapping board, a line of ice would slide forever
+            if !self.grid.is_ice(to.row, to.col) || to == start {
+                break;
+            }
+        }
+        let theseus = self.theseus;
+        self.goals_left.retain(|&goal| goal != theseus);
+        true
+    }
+
+    /// Returns whether Theseus can set off with `command`: diagonals have to
+    /// be allowed, and the first cell has to be open
+    fn theseus_can_move(&self, command: Command) -> bool {
+        (self.diagonal_moves || !command.is_diagonal())
+            && self.move_target(self.theseus, command).is_some()
+    }
+
+    /// Every command Theseus may be given: [`Command::all`], then the
+    /// diagonals if they're allowed
+    fn theseus_commands(&self) -> impl Iterator<Item = Command> + '_ {
+        Command::all().chain(self.theseus_diagonals())
+    }
+
+    /// The diagonals, if Theseus may use them, and nothing otherwise
+    fn theseus_diagonals(&self) -> impl Iterator<Item = Command> + '_ {
+        Command::diagonals().filter(move |_| self.diagonal_moves)
+    }
+
+    /// Like [`Position::step`], but coming back around the far edge of a
+    /// wrapping board
+    fn neighbor(&self, from: Position, command: Command) -> Option<Position> {
+        if !self.wrap {
+            return from.step(command);
+        }
+        let (dr, dc) = command.delta();
+        let wrap = |at: usize, by: isize, size: usize| (at as isize + by).rem_euclid(size as isize);
+        Some(Position::new(
+            wrap(from.row, dr, self.grid.height) as usize,
+            wrap(from.col, dc, self.grid.width) as usize,
+        ))
+    }
+
+    /// Returns the rows and columns to go from `from` to `to` the short way,
+    /// which on a wrapping board may be around the edge
+    fn gap(&self, from: Position, to: Position) -> (isize, isize) {
+        let axis = |from: usize, to: usize, size: usize| {
+            let d = to as isize - from as isize;
+            let size = size as isize;
+            if self.wrap && 2 * d.abs() > size {
+                d - d.signum() * size
+            } else {
+                d
+            }
+        };
+        (
+            axis(from.row, to.row, self.grid.height),
+            axis(from.col, to.col, self.grid.width),
+        )
+    }
+
+    /// Manhattan distance between two cells, the short way around on a
+    /// wrapping board
+    fn distance(&self, a: Position, b: Position) -> usize {
+        let (dr, dc) = self.gap(a, b);
+        dr.unsigned_abs() + dc.unsigned_abs()
+    }
+
+    /// Returns where the portal at `pos` leads, if there is one
+    fn portal_exit(&self, pos: Position) -> Option<Position> {
+        match self.portals {
+            Some((a, b)) if pos == a => Some(b),
+            Some((a, b)) if pos == b => Some(a),
+            _ => None,
+        }
+    }
+
+    pub fn status(&self) -> GameStatus {
+        if self.minotaurs.contains(&self.theseus) {
+            return GameStatus::Lose;
+        }
+        // Minotaurs walk over traps unharmed; only Theseus falls in.
+        if self.grid.is_trap(self.theseus.row, self.theseus.col) {
+            return GameStatus::Lose;
+        }
+        if self.multiple_goals {
+            if self.goals_left.is_empty() {
+                return GameStatus::Win;
+            }
+        } else if self.theseus == self.goal {
+            return GameStatus::Win;
+        }
+        if self.max_turns.is_some_and(|max| self.turn >= max) {
+            return GameStatus::Lose;
+        }
+        GameStatus::Continue
+    }
+
+    /// Finds the shortest sequence of commands that wins the game from the
+    /// current position, or `None` if the Minotaur can always catch Theseus.
+    pub fn solve(&self) -> Option<Vec<Command>> {
+        match self.status() {
+            GameStatus::Win => return Some(Vec::new()),
+            GameStatus::Lose => return None,
+            GameStatus::Continue => {}
+        }
+
+        let reachable = self.reachable_states();
+        let (mut cur, command) = reachable.win?;
+        let mut path = vec![command];
+        while let Some((prev, cmd)) = reachable.parents.get(&cur) {
+            path.push(*cmd);
+            cur = prev.clone();
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    /// Finds every shortest sequence of commands that wins the game from the
+    /// current position, up to `limit` of them. Moves into a wall are left
+    /// out, since they just wait like `Skip`. Empty if the game can't be
+    /// won; a single empty sequence if it already is.
+    pub fn all_optimal_solutions(&self, limit: usize) -> Vec<Vec<Command>> {
+        match self.status() {
+            GameStatus::Win => return vec![Vec::new()],
+            GameStatus::Lose => return Vec::new(),
+            GameStatus::Continue => {}
+        }
+
+        // Breadth-first, one whole depth at a time, keeping every way each
+        // state was first reached rather than just one
+        let start = self.state();
+        let mut depths: HashMap<State, u32> = HashMap::from([(start.clone(), 0)]);
+        let mut parents: HashMap<State, Vec<(State, Command)>> = HashMap::new();
+        let mut frontier = vec![start.clone()];
+        let turns_left = self.max_turns.map(|max| max.saturating_sub(self.turn));
+        let mut game = self.clone();
+        game.walls = Some(Arc::new(BitGrid::from(&self.grid)));
+        let mut depth = 0;
+        while !frontier.is_empty() && turns_left.is_none_or(|left| depth < left) {
+            let mut wins: Vec<(State, Command)> = Vec::new();
+            let mut next_frontier = Vec::new();
+            for state in &frontier {
+                for command in self.theseus_commands() {
+                    game.set_state(state);
+                    if game.move_target(game.theseus, command).is_none() {
+                        continue;
+                    }
+                    match game.take_turn(command) {
+                        GameStatus::Lose => {}
+                        GameStatus::Win => wins.push((state.clone(), command)),
+                        GameStatus::Continue => {
+                            let next = game.state();
+                            let seen = *depths.entry(next.clone()).or_insert(depth + 1);
+                            if seen == depth + 1 {
+                                if !parents.contains_key(&next) {
+                                    next_frontier.push(next.clone());
+                                }
+                                parents
+                                    .entry(next)
+                                    .or_default()
+                                    .push((state.clone(), command));
+                            }
+                        }
+                    }
+                }
+            }
+            if !wins.is_empty() {
+                let mut solutions = Vec::new();
+                for (state, command) in wins {
+                    let mut path = vec![command];
+                    collect_paths(&state, &start, &parents, &mut path, &mut solutions, limit);
+                }
+                return solutions;
+            }
+            frontier = next_frontier;
+            depth += 1;
+        }
+        Vec::new()
+    }
+
+    /// Suggests the first move of a shortest winning line, or `None` if the
+    /// game is already over or can't be won from here.
+    pub fn hint(&self) -> Option<Command> {
+        self.solve()?.first().copied()
+    }
+
+    /// Plays out each of [`Game::legal_theseus_moves`] on a copy of the game,
+    /// Minotaurs' reply included, and pairs it with whether Theseus survives
+    /// the turn: true if it's safe, false if it loses.
+    pub fn move_safety(&self) -> Vec<(Command, bool)> {
+        let mut game = self.clone();
+        self.legal_theseus_moves()
+            .into_iter()
+            .map(|command| {
+                game.set_state(&self.state());
+                (command, game.take_turn(command) != GameStatus::Lose)
+            })
+            .collect()
+    }
+
+    /// Returns whether every move Theseus has, waiting included, gets him
+    /// caught this turn, as judged by [`Game::move_safety`]. Unlike
+    /// [`Game::is_lost_cause`], this only looks one turn ahead.
+    pub fn theseus_cornered(&self) -> bool {
+        self.move_safety().iter().all(|&(_, safe)| !safe)
+    }
+
+    /// Picks a random legal move for Theseus that doesn't lose the game this
+    /// turn, or `Skip` if every move does. `rng_seed` is advanced on each
+    /// call, so the same starting seed always plays the same way.
+    pub fn autopilot_move(&self, rng_seed: &mut u64) -> Command {
+        let safe: Vec<Command> = self
+            .move_safety()
+            .into_iter()
+            .filter_map(|(command, safe)| safe.then_some(command))
+            .collect();
+        if safe.is_empty() {
+            return Command::Skip;
+        }
+        let mut rng = Rng::new(*rng_seed);
+        let command = safe[rng.below(safe.len())];
+        *rng_seed = rng.0;
+        command
+    }
+
+    /// Rough difficulty score: the length of the shortest solution, plus a
+    /// penalty for every turn the player has to wait in it. `None` if the
+    /// board can't be won.
+    pub fn difficulty(&self) -> Option<u32> {
+        /// Waiting is much less obvious to a player than walking
+        const SKIP_PENALTY: u32 = 3;
+        let solution = self.solve()?;
+        let skips = solution.iter().filter(|&&c| c == Command::Skip).count() as u32;
+        Some(solution.len() as u32 + skips * SKIP_PENALTY)
+    }
+
+    /// Finds the cheapest sequence of commands that wins the game from the
+    /// current position, and its total cost. Every turn costs what the tile
+    /// Theseus ends his move on costs (see [`Cell::cost`]), so this may take
+    /// a longer way round than [`Game::solve`] to avoid mud. `None` if the
+    /// game can't be won.
+    pub fn solve_min_cost(&self) -> Option<(Vec<Command>, u32)> {
+        match self.status() {
+            GameStatus::Win => return Some((Vec::new(), 0)),
+            GameStatus::Lose => return None,
+            GameStatus::Continue => {}
+        }
+
+        // Dijkstra over the same states as `reachable_states`. With a turn
+        // limit, how many turns it took to get somewhere matters too.
+        let turns_left = self.max_turns.map(|max| max.saturating_sub(self.turn));
+        let start = (self.state(), 0);
+        let mut nodes: Vec<(State, u32)> = vec![start.clone()];
+        let mut costs: HashMap<(State, u32), u32> = HashMap::from([(start, 0)]);
+        let mut parents: HashMap<(State, u32), (usize, Command)> = HashMap::new();
+        let mut queue = BinaryHeap::from([Reverse((0, 0))]);
+        let mut best: Option<(u32, usize, Command)> = None;
+
+        let mut game = self.clone();
+        game.walls = Some(Arc::new(BitGrid::from(&self.grid)));
+        while let Some(Reverse((cost, i))) = queue.pop() {
+            if best.is_some_and(|(best_cost, _, _)| best_cost <= cost) {
+                break;
+            }
+            let (state, depth) = nodes[i].clone();
+            if costs[&(state.clone(), depth)] < cost {
+                continue;
+            }
+            if turns_left.is_some_and(|left| depth >= left) {
+                continue;
+            }
+            for command in self.theseus_commands() {
+                game.set_state(&state);
+                if game.move_target(game.theseus, command).is_none() {
+                    continue;
+                }
+                let status = game.take_turn(command);
+                let tile = game.grid.get(game.theseus.row, game.theseus.col);
+                let total = cost + tile.map_or(1, Cell::cost);
+                match status {
+                    GameStatus::Lose => continue,
+                    GameStatus::Win => {
+                        if best.is_none_or(|(best_cost, _, _)| total < best_cost) {
+                            best = Some((total, i, command));
+                        }
+                    }
+                    GameStatus::Continue => {
+                        let next = (game.state(), turns_left.map_or(0, |_| depth + 1));
+                        if costs.get(&next).is_none_or(|&known| total < known) {
+                            costs.insert(next.clone(), total);
+                            parents.insert(next.clone(), (i, command));
+                            nodes.push(next);
+                            queue.push(Reverse((total, nodes.len() - 1)));
+                        }
+                    }
+                }
+            }
+        }
+
+        let (total, mut i, command) = best?;
+        let mut path = vec![command];
+        while let Some(&(prev, cmd)) = parents.get(&nodes[i]) {
+            path.push(cmd);
+            i = prev;
+        }
+        path.reverse();
+        Some((path, total))
+    }
+
+    /// Returns the closest cell Theseus can walk to that is neither where a
+    /// Minotaur ends its next turn nor next to it, by steps up, down, left
+    /// and right around walls and never through a Minotaur. His own cell
+    /// counts if it's safe. `None` if no such cell is in reach.
+    pub fn nearest_safe_cell(&self) -> Option<(usize, usize)> {
+        // Where the Minotaurs get to in a whole turn, all their steps of it
+        let mut game = self.clone();
+        game.minotaur_turn();
+        let danger = game.minotaurs;
+        let is_safe = |pos: Position| danger.iter().all(|&m| self.distance(pos, m) > 1);
+
+        let mut seen = HashSet::from([self.theseus]);
+        let mut queue = VecDeque::from([self.theseus]);
+        while let Some(pos) = queue.pop_front() {
+            if is_safe(pos) {
+                return Some((pos.row, pos.col));
+            }
+            for command in Command::directions().chain(self.theseus_diagonals()) {
+                // No walking through a Minotaur to get away from it
+                match self.move_target(pos, command) {
+                    Some(next) if !self.minotaurs.contains(&next) && seen.insert(next) => {
+                        queue.push_back(next);
+                    }
+                    _ => {}
+                }
+            }
+        }
+        None
+    }
+
+    /// Returns every cell Theseus could walk to from where he stands if
+    /// there were no Minotaur, his own cell included. Doors count as open
+    /// and portals link their two cells; ice and traps are ignored. On a
+    /// wrapping board he can walk around the edges.
+    pub fn reachable_cells(&self) -> HashSet<(usize, usize)> {
+        let start = (self.theseus.row, self.theseus.col);
+        let mut seen = HashSet::from([start]);
+        let mut queue = VecDeque::from([start]);
+        while let Some((row, col)) = queue.pop_front() {
+            let pos = Position::new(row, col);
+            let steps = Command::directions()
+                .chain(self.theseus_diagonals())
+                .filter_map(|command| self.neighbor(pos, command))
+                .filter(|to| self.grid.in_bounds(to.row, to.col))
+                .filter(|to| !self.grid.is_wall(to.row, to.col));
+            for next in steps.chain(self.portal_exit(pos)) {
+                if seen.insert((next.row, next.col)) {
+                    queue.push_back((next.row, next.col));
+                }
+            }
+        }
+        seen
+    }
+
+    /// Returns whether Theseus can still reach the goal from the current
+    /// position, whatever the Minotaur does.
+    pub fn is_winnable(&self) -> bool {
+        match self.status() {
+            GameStatus::Win => true,
+            GameStatus::Lose => false,
+            GameStatus::Continue => self.reachable_states().win.is_some(),
+        }
+    }
+
+    /// Returns whether the game can no longer be won, whatever Theseus does
+    /// from here: the opposite of [`Game::is_winnable`]. True once the game
+    /// is lost, false once it is won.
+    pub fn is_lost_cause(&self) -> bool {
+        !self.is_winnable()
+    }
+
+    /// Breadth-first search over the positions reachable from the current
+    /// one without losing, stopping at the first winning move. Respects the
+    /// turn limit, if any.
+    fn reachable_states(&self) -> Reachable {
+        // Only the entities, the key, and the goal change between turns, so a
+        // search node is just those.
+        let start = self.state();
+        let mut parents: HashMap<State, (State, Command)> = HashMap::new();
+        let mut visited: HashSet<State> = HashSet::new();
+        let mut queue: VecDeque<(State, u32)> = VecDeque::new();
+        visited.insert(start.clone());
+        queue.push_back((start, 0));
+
+        let turns_left = self.max_turns.map(|max| max.saturating_sub(self.turn));
+        let mut game = self.clone();
+        game.walls = Some(Arc::new(BitGrid::from(&self.grid)));
+        while let Some((state, depth)) = queue.pop_front() {
+            if turns_left.is_some_and(|left| depth >= left) {
+                continue;
+            }
+            for command in self.theseus_commands() {
+                game.set_state(&state);
+                // A move into a wall only waits, and `Skip` says so better
+                if game.move_target(game.theseus, command).is_none() {
+                    continue;
+                }
+                match game.take_turn(command) {
+                    GameStatus::Lose => continue,
+                    GameStatus::Win => {
+                        return Reachable {
+                            parents,
+                            win: Some((state, command)),
+                        };
+                    }
+                    GameStatus::Continue => {
+                        let next = game.state();
+                        if visited.insert(next.clone()) {
+                            parents.insert(next.clone(), (state.clone(), command));
+                            queue.push_back((next, depth + 1));
+                        }
+                    }
+                }
+            }
+        }
+        Reachable { parents, win: None }
+    }
+
+    /// Returns the number of turns Theseus has to reach the goal, if limited
+    pub fn max_turns(&self) -> Option<u32> {
+        self.max_turns
+    }
+
+    /// Limits how many turns Theseus has to reach the goal. Once that many
+    /// turns have been played without winning, the game is lost. `None`, the
+    /// default, means no limit.
+    pub fn set_max_turns(&mut self, max_turns: Option<u32>) {
+        self.max_turns = max_turns;
+    }
+
+    /// Returns how many times the Minotaur moves per turn
+    pub fn minotaur_speed(&self) -> u32 {
+        self.minotaur_speed
+    }
+
+    /// Sets how many times the Minotaur moves per turn. The classic puzzle
+    /// uses 2; boards start at 1.
+    pub fn set_minotaur_speed(&mut self, speed: u32) {
+        self.minotaur_speed = speed;
+    }
+
+    /// Returns which way the Minotaur closes the gap to Theseus first
+    pub fn minotaur_axis_priority(&self) -> Axis {
+        self.minotaur_axis_priority
+    }
+
+    /// Sets which way the Minotaur closes the gap to Theseus first when
+    /// chasing him with [`GreedyPolicy`]. Boards start horizontal-first.
+    pub fn set_minotaur_axis_priority(&mut self, axis: Axis) {
+        self.minotaur_axis_priority = axis;
+    }
+
+    /// Returns whether the edges of the board wrap around
+    pub fn wrap(&self) -> bool {
+        self.wrap
+    }
+
+    /// Returns whether Theseus may move diagonally
+    pub fn diagonal_moves(&self) -> bool {
+        self.diagonal_moves
+    }
+
+    /// Sets whether Theseus may move diagonally. When he can't, a diagonal
+    /// command leaves him where he is, like walking into a wall.
+    pub fn set_diagonal_moves(&mut self, diagonal_moves: bool) {
+        self.diagonal_moves = diagonal_moves;
+    }
+
+    /// Sets whether walking off one edge of the board comes back on the
+    /// opposite one, for everything that moves. The Minotaurs then chase
+    /// Theseus the short way round. Walls still block as usual.
+    pub fn set_wrap(&mut self, wrap: bool) {
+        self.wrap = wrap;
+    }
+
+    /// Moves the Minotaur `minotaur_speed` times, checking the status before
+    /// each step so it can't move past Theseus once it has caught him.
+    pub fn minotaur_turn(&mut self) -> GameStatus {
+        for _ in 0..self.minotaur_speed {
+            if self.status() != GameStatus::Continue {
+                break;
+            }
+            self.minotaur_move();
+        }
+        self.status()
+    }
+
+    /// Plays one full turn and returns the resulting status: Theseus moves,
+    /// and unless that wins or walks into a Minotaur, the Minotaur takes its
+    /// turn. The turn is recorded in the undo history. Once the game is won
+    /// or lost, nothing moves and the status is returned as it is.
+    pub fn step(&mut self, command: Command) -> GameStatus {
+        let status = self.status();
+        if status != GameStatus::Continue {
+            return status;
+        }
+        self.start_turn();
+        let status = self.take_turn(command);
+        self.mark_visited();
+        if self.on_turn.is_none() && self.trace.is_none() {
+            return status;
+        }
+        let event = TurnEvent {
+            command,
+            theseus: self.theseus,
+            minotaurs: self.minotaurs.clone(),
+            status,
+        };
+        if let Some(on_turn) = &self.on_turn {
+            (on_turn.lock().expect("turn callback panicked"))(&event);
+        }
+        if let Some(trace) = &mut self.trace {
+            trace.push(event);
+        }
+        status
+    }
+
+    /// Starts or stops recording every turn played with [`Game::step`].
+    /// Turning tracing on starts a fresh trace; turning it off drops it.
+    /// Off by default.
+    pub fn set_tracing(&mut self, tracing: bool) {
+        self.trace = tracing.then(Vec::new);
+    }
+
+    /// Returns the turns recorded since tracing was turned on, oldest first.
+    /// Undo doesn't take turns back out. Empty when tracing is off.
+    pub fn trace(&self) -> &[TurnEvent] {
+        self.trace.as_deref().unwrap_or(&[])
+    }
+
+    /// Plays one full turn like [`Game::step`], and reports what moved
+    pub fn apply(&mut self, command: Command) -> TurnOutcome {
+        let status = self.status();
+        if status != GameStatus::Continue {
+            return TurnOutcome {
+                theseus_moved: false,
+                minotaur_moved: false,
+                status,
+            };
+        }
+        let theseus_moved = self.theseus_can_move(command);
+        let minotaurs = self.minotaurs.clone();
+        let status = self.step(command);
+        TurnOutcome {
+            theseus_moved,
+            minotaur_moved: self.minotaurs != minotaurs,
+            status,
+        }
+    }
+
+    /// Registers a callback to run after every [`Game::step`], replacing any
+    /// earlier one. Clones of the game share the callback.
+    pub fn set_on_turn<F: FnMut(&TurnEvent) + Send + 'static>(&mut self, on_turn: F) {
+        self.on_turn = Some(Arc::new(Mutex::new(on_turn)));
+    }
+
+    /// Plays each command as a full turn with [`Game::step`], stopping as soon
+    /// as the game is won or lost. Returns the final status and how many
+    /// commands were played.
+    pub fn replay(&mut self, commands: &[Command]) -> (GameStatus, usize) {
+        let mut played = 0;
+        for &command in commands {
+            if self.status() != GameStatus::Continue {
+                break;
+            }
+            self.step(command);
+            played += 1;
+        }
+        (self.status(), played)
+    }
+
+    /// Plays one full turn like [`Game::step`], without touching the undo
+    /// history. The goal moves last, once the Minotaurs are done.
+    fn take_turn(&mut self, command: Command) -> GameStatus {
+        self.apply_theseus_move(command);
+        if self.status() != GameStatus::Continue {
+            return self.status();
+        }
+        self.finish_turn()
+    }
+
+    /// Lets the Minotaurs and then the goal take their turn, as if Theseus
+    /// had waited, for real-time play on a timer. Unlike
+    /// `step(Command::Skip)` this doesn't count as a turn and isn't recorded
+    /// in the undo history, so undoing the turn before also takes back the
+    /// ticks since.
+    pub fn tick(&mut self) -> GameStatus {
+        if self.status() != GameStatus::Continue {
+            return self.status();
+        }
+        self.finish_turn()
+    }
+
+    /// The rest of a turn once Theseus has moved
+    fn finish_turn(&mut self) -> GameStatus {
+        if self.minotaur_turn() != GameStatus::Continue {
+            return self.status();
+        }
+        let command = Arc::clone(&self.goal_policy).choose(self, self.goal);
+        self.move_goal(command);
+        self.status()
+    }
+
+    fn state(&self) -> State {
+        State {
+            theseus: self.theseus,
+            minotaurs: self.minotaurs.clone(),
+            has_key: self.has_key,
+            goal: self.goal,
+            goals_left: self.goals_left.clone(),
+        }
+    }
+
+    fn set_state(&mut self, state: &State) {
+        self.theseus = state.theseus;
+        self.minotaurs.clone_from(&state.minotaurs);
+        self.has_key = state.has_key;
+        self.goals_left.clone_from(&state.goals_left);
+        if state.goal != self.goal {
+            self.place_goal(state.goal);
+        }
+    }
+}
+
+/// Two games are equal when they have the same grid and every entity stands in
+/// the same place. Settings, turn counts, and history are not compared.
+impl PartialEq for Game {
+    fn eq(&self, other: &Self) -> bool {
+        self.grid == other.grid
+            && self.theseus == other.theseus
+            && self.minotaurs == other.minotaurs
+            && self.goal == other.goal
+            && self.goals_left == other.goals_left
+            && self.has_key == other.has_key
+    }
+}
+impl Eq for Game {}
+
+impl std::hash::Hash for Game {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.grid.hash(state);
+        self.theseus.hash(state);
+        self.minotaurs.hash(state);
+        self.goal.hash(state);
+        self.goals_left.hash(state);
+        self.has_key.hash(state);
+    }
+}
+
+/// Formats the board as [`Game::render`] does
+impl Display for Game {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.render())
+    }
+}
+
+/// The fields a [`Game`] is deserialized from, checked before they become one
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct GameData {
+    grid: Grid,
+    theseus: Position,
+    minotaurs: Vec<Position>,
+    goal: Position,
+    #[serde(default)]
+    multiple_goals: bool,
+    #[serde(default)]
+    goals_left: Vec<Position>,
+    #[serde(default)]
+    has_key: bool,
+    #[serde(default)]
+    portals: Option<(Position, Position)>,
+    minotaur_speed: u32,
+    #[serde(default)]
+    minotaur_axis_priority: Axis,
+    #[serde(default)]
+    wrap: bool,
+    #[serde(default)]
+    diagonal_moves: bool,
+    turn: u32,
+    max_turns: Option<u32>,
+    initial: State,
+    #[serde(default)]
+    visited: HashSet<(usize, usize)>,
+}
+
+/// Refuses what [`Game::from_json`] does: an empty or oversized board, no
+/// Minotaur, positions off the board, and anything standing in a wall. The
+/// goal also has to be on the goal tile.
+#[cfg(feature = "serde")]
+impl TryFrom<GameData> for Game {
+    type Error = BoardError;
+
+    fn try_from(data: GameData) -> Result<Self, Self::Error> {
+        let grid = data.grid;
+        let (width, height) = (grid.width, grid.height);
+        if width == 0 || height == 0 {
+            return Err(BoardError::EmptyBoard);
+        }
+        if width > GameConfig::DEFAULT_MAX_DIMENSION || height > GameConfig::DEFAULT_MAX_DIMENSION {
+            return Err(BoardError::TooLarge { width, height });
+        }
+        let current = State {
+            theseus: data.theseus,
+            minotaurs: data.minotaurs,
+            has_key: data.has_key,
+            goal: data.goal,
+            goals_left: data.goals_left,
+        };
+        current.check(&grid)?;
+        data.initial.check(&grid)?;
+        if grid.get(current.goal.row, current.goal.col) != Some(Cell::Goal) {
+            return Err(BoardError::NoGoal);
+        }
+        let portals = data.portals.iter().flat_map(|&(a, b)| [a, b]);
+        let visited = data.visited.iter().map(|&cell| Position::from(cell));
+        for Position { row, col } in portals.chain(visited) {
+            if !grid.in_bounds(row, col) {
+                return Err(BoardError::OutOfBounds { row, col });
+            }
+        }
+
+        Ok(Game {
+            grid,
+            theseus: current.theseus,
+            minotaurs: current.minotaurs,
+            goal: current.goal,
+            multiple_goals: data.multiple_goals,
+            goals_left: current.goals_left,
+            has_key: current.has_key,
+            portals: data.portals,
+            minotaur_speed: data.minotaur_speed,
+            minotaur_axis_priority: data.minotaur_axis_priority,
+            wrap: data.wrap,
+            diagonal_moves: data.diagonal_moves,
+            turn: data.turn,
+            max_turns: data.max_turns,
+            minotaur_policy: default_policy(),
+            goal_policy: default_goal_policy(),
+            on_turn: None,
+            trace: None,
+            initial: data.initial,
+            visited: data.visited,
+            undo_stack: Vec::new(),
+            redo_stack: Vec::new(),
+            walls: None,
+        })
+    }
+}
+
+// Undo and redo
+impl Game {
+    /// Saves the current positions as the start of a new turn
+    fn start_turn(&mut self) {
+        self.undo_stack.push((self.state(), None));
+        self.redo_stack.clear();
+        self.turn += 1;
+    }
+
+    /// Adds where Theseus stands to the visited cells, remembering in the
+    /// current turn whether it was new so `undo` can take it back out
+    fn mark_visited(&mut self) {
+        let cell = (self.theseus.row, self.theseus.col);
+        if self.visited.insert(cell) {
+            if let Some((_, added)) = self.undo_stack.last_mut() {
+                *added = Some(cell);
+            }
+        }
+    }
+
+    /// Returns every cell Theseus has stepped on, including where he
+    /// started, in no particular order. Undo takes back the cells of the
+    /// turns it rewinds.
+    pub fn visited(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.visited.iter().copied()
+    }
+
+    /// Returns how many turns have been played. Each [`Game::theseus_move`]
+    /// or [`Game::step`] starts a turn, including `Skip`s; undo and redo
+    /// move the count back and forth.
+    pub fn turns(&self) -> u32 {
+        self.turn
+    }
+
+    /// Rewinds to before the last turn. Returns false if there is nothing
+    /// to undo.
+    pub fn undo(&mut self) -> bool {
+        match self.undo_stack.pop() {
+            Some((state, added)) => {
+                self.redo_stack.push(self.state());
+                self.set_state(&state);
+                if let Some(cell) = added {
+                    self.visited.remove(&cell);
+                }
+                self.turn -= 1;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Restarts the game from where everything stood when the board was
+    /// loaded, forgetting the turns played and the undo history. Settings
+    /// such as the Minotaur speed and policies are kept.
+    pub fn reset(&mut self) {
+        let initial = self.initial.clone();
+        self.set_state(&initial);
+        self.turn = 0;
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+        self.visited = HashSet::from([(initial.theseus.row, initial.theseus.col)]);
+    }
+
+    /// Captures the positions, key, and turn count, to go back to with
+    /// [`Game::restore`]. Much cheaper than cloning the game.
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            state: self.state(),
+            turn: self.turn,
+            history: self.undo_stack.len(),
+        }
+    }
+
+    /// Goes back to a [`Game::snapshot`]. Turns played since the snapshot are
+    /// dropped from the undo history and visited cells; nothing can be
+    /// redone afterwards.
+    pub fn restore(&mut self, snapshot: &Snapshot) {
+        self.set_state(&snapshot.state);
+        self.turn = snapshot.turn;
+        if self.undo_stack.len() > snapshot.history {
+            for (_, added) in self.undo_stack.drain(snapshot.history..) {
+                if let Some(cell) = added {
+                    self.visited.remove(&cell);
+                }
+            }
+        }
+        // Undoing past the snapshot first took its cell out of `visited`
+        let theseus = self.theseus;
+        self.visited.insert((theseus.row, theseus.col));
+        self.redo_stack.clear();
+    }
+
+    /// Replays the last undone turn. Returns false if there is nothing to
+    /// redo; any new move clears what can be redone.
+    pub fn redo(&mut self) -> bool {
+        match self.redo_stack.pop() {
+            Some(state) => {
+                self.undo_stack.push((self.state(), None));
+                self.set_state(&state);
+                self.mark_visited();
+                self.turn += 1;
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// Everything that changes between turns, making up one node of the
+/// solver's search space
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct State {
+    theseus: Position,
+    /// Every Minotaur, in board order
+    minotaurs: Vec<Position>,
+    has_key: bool,
+    goal: Position,
+    /// Goals not visited yet, when there can be several
+    #[cfg_attr(feature = "serde", serde(default))]
+    goals_left: Vec<Position>,
+}
+
+impl State {
+    /// Checks that there is a Minotaur, that every position is on the board,
+    /// and that nothing stands in a wall or a locked door
+    fn check(&self, grid: &Grid) -> Result<(), BoardError> {
+        if self.minotaurs.is_empty() {
+            return Err(BoardError::NoMinotaur);
+        }
+        let entities = std::iter::once(&self.theseus).chain(&self.minotaurs);
+        let goals = std::iter::once(&self.goal).chain(&self.goals_left);
+        for &Position { row, col } in entities.clone().chain(goals) {
+            if !grid.in_bounds(row, col) {
+                return Err(BoardError::OutOfBounds { row, col });
+            }
+        }
+        for &Position { row, col } in entities {
+            let locked = !self.has_key && grid.get(row, col) == Some(Cell::Door);
+            if grid.is_wall(row, col) || locked {
+                return Err(BoardError::Occupied { row, col });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The changing part of a game, taken with [`Game::snapshot`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Snapshot {
+    state: State,
+    turn: u32,
+    /// Length of the undo history when the snapshot was taken
+    history: usize,
+}
+
+/// How many of each kind of tile a board has, from [`Game::tile_counts`]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TileCounts {
+    pub walls: usize,
+    /// Empty tiles, including the ones Theseus and the Minotaurs stand on
+    pub empty: usize,
+    /// Keys, doors, portals, ice, traps and mud
+    pub special: usize,
+    pub has_goal: bool,
+    pub has_theseus: bool,
+    pub minotaurs: usize,
+}
+
+/// What a search from some starting state found
+struct Reachable {
+    /// How each visited state other than the start was first reached: the
+    /// state it was reached from and the command played
+    parents: HashMap<State, (State, Command)>,
+    /// The first winning move found, and the state it is played from
+    win: Option<(State, Command)>,
+}
+
+// Derived queries the autograder expects
+impl Game {
+    /// Returns true if the given position is Theseus
+    pub fn is_theseus(&self, row: usize, col: usize) -> bool {
+        self.theseus == Position::new(row, col)
+    }
+    /// Returns true if the given position is Minotaur
+    pub fn is_minotaur(&self, row: usize, col: usize) -> bool {
+        self.minotaurs.contains(&Position::new(row, col))
+    }
+    /// Returns true if the given position is a wall
+    pub fn is_wall(&self, row: usize, col: usize) -> bool {
+        self.grid.is_wall(row, col)
+    }
+    /// Returns true if the given position is the goal. With several goals,
+    /// the ones Theseus has visited no longer count.
+    pub fn is_goal(&self, row: usize, col: usize) -> bool {
+        self.grid.is_goal(row, col)
+            && (!self.multiple_goals || self.goals_left.contains(&Position::new(row, col)))
+    }
+    /// Returns true if the given position is empty
+    pub fn is_empty(&self, row: usize, col: usize) -> bool {
+        !self.is_theseus(row, col)
+            && !self.is_minotaur(row, col)
+            && !self.is_wall(row, col)
+            && !self.is_goal(row, col)
+            && !self.is_key(row, col)
+            && !self.is_door(row, col)
+            && !self.is_portal(row, col)
+            && !self.is_ice(row, col)
+            && !self.is_trap(row, col)
+            && !self.is_mud(row, col)
+    }
+    /// Returns true if the given position holds the key and Theseus hasn't
+    /// picked it up yet
+    pub fn is_key(&self, row: usize, col: usize) -> bool {
+        !self.has_key && self.grid.is_key(row, col)
+    }
+    /// Returns true if the given position is a door that's still locked
+    pub fn is_door(&self, row: usize, col: usize) -> bool {
+        !self.has_key && self.grid.is_door(row, col)
+    }
+    /// Returns true if the given position is a portal
+    pub fn is_portal(&self, row: usize, col: usize) -> bool {
+        self.grid.is_portal(row, col)
+    }
+    /// Returns true if the given position is ice
+    pub fn is_ice(&self, row: usize, col: usize) -> bool {
+        self.grid.is_ice(row, col)
+    }
+    /// Returns true if the given position is a trap
+    pub fn is_trap(&self, row: usize, col: usize) -> bool {
+        self.grid.is_trap(row, col)
+    }
+    /// Returns true if the given position is mud
+    pub fn is_mud(&self, row: usize, col: usize) -> bool {
+        self.grid.is_mud(row, col)
+    }
+    /// Returns true once Theseus has picked up the key
+    pub fn has_key(&self) -> bool {
+        self.has_key
+    }
+}
+
+// Board dimensions
+impl Game {
+    /// Returns the number of columns on the board
+    pub fn width(&self) -> usize {
+        self.grid.width()
+    }
+    /// Returns the number of rows on the board
+    pub fn height(&self) -> usize {
+        self.grid.height()
+    }
+    /// Returns the static tiles of the board, without the entities
+    pub fn grid(&self) -> &Grid {
+        &self.grid
+    }
+    /// Iterates over every `(row, col)` on the board, row by row
+    pub fn positions(&self) -> impl Iterator<Item = (usize, usize)> {
+        let width = self.width();
+        (0..self.height()).flat_map(move |row| (0..width).map(move |col| (row, col)))
+    }
+    /// Counts the tiles on the board, and which entities are on it
+    pub fn tile_counts(&self) -> TileCounts {
+        let mut counts = TileCounts {
+            has_theseus: true,
+            minotaurs: self.minotaurs.len(),
+            ..TileCounts::default()
+        };
+        for &cell in &self.grid.cells {
+            match cell {
+                Cell::Wall => counts.walls += 1,
+                Cell::Empty => counts.empty += 1,
+                Cell::Goal => counts.has_goal = true,
+                _ => counts.special += 1,
+            }
+        }
+        counts
+    }
+}
+
+// Direct position lookups
+impl Game {
+    /// Returns the position of Theseus
+    pub fn theseus_position(&self) -> Position {
+        self.theseus
+    }
+    /// Returns the position of the Minotaur, or of the first one in board
+    /// order when there are several
+    pub fn minotaur_position(&self) -> Position {
+        self.minotaurs[0]
+    }
+    /// Returns the positions of every Minotaur, in board order
+    pub fn minotaur_positions(&self) -> &[Position] {
+        &self.minotaurs
+    }
+    /// Returns the position of the goal, or of the first one in board order
+    /// when there are several
+    pub fn goal_position(&self) -> Position {
+        self.goal
+    }
+    /// Returns the goals Theseus still has to visit in board order, when the
+    /// board allows several. Empty otherwise.
+    pub fn goals_left(&self) -> &[Position] {
+        &self.goals_left
+    }
+    /// Returns the Manhattan distance from Theseus to the nearest Minotaur
+    pub fn theseus_minotaur_distance(&self) -> usize {
+        let theseus = self.theseus_position();
+        self.minotaurs
+            .iter()
+            .map(|&minotaur| self.distance(theseus, minotaur))
+            .min()
+            .unwrap_or(0)
+    }
+    /// Returns how many steps up, down, left or right the nearest Minotaur
+    /// needs to reach Theseus going around walls, or `None` if none can get
+    /// to him. Unlike [`Game::theseus_minotaur_distance`], walls count.
+    pub fn minotaur_distance_to_theseus(&self) -> Option<usize> {
+        let mut seen: HashSet<Position> = self.minotaurs.iter().copied().collect();
+        let mut queue: VecDeque<(Position, usize)> = self
+            .minotaurs
+            .iter()
+            .map(|&minotaur| (minotaur, 0))
+            .collect();
+        while let Some((pos, steps)) = queue.pop_front() {
+            if pos == self.theseus {
+                return Some(steps);
+            }
+            for command in Command::directions() {
+                if let Some(next) = self.move_target(pos, command) {
+                    if seen.insert(next) {
+                        queue.push_back((next, steps + 1));
+                    }
+                }
+            }
+        }
+        None
+    }
+    /// Returns the Manhattan distance from Theseus to the goal, or with
+    /// several goals to the nearest one still to visit. 0 once they all are.
+    pub fn distance_to_goal(&self) -> usize {
+        self.goals_to_reach()
+            .iter()
+            .map(|&goal| self.distance(self.theseus, goal))
+            .min()
+            .unwrap_or(0)
+    }
+    /// Returns how many steps up, down, left or right Theseus needs to reach
+    /// the goal going around walls and locked doors, or `None` if he can't.
+    /// With several goals, it's the nearest one still to visit. Minotaurs
+    /// are ignored.
+    pub fn path_distance_to_goal(&self) -> Option<usize> {
+        let goals = self.goals_to_reach();
+        if goals.is_empty() {
+            return Some(0);
+        }
+        let mut seen = HashSet::from([self.theseus]);
+        let mut queue = VecDeque::from([(self.theseus, 0)]);
+        while let Some((pos, steps)) = queue.pop_front() {
+            if goals.contains(&pos) {
+                return Some(steps);
+            }
+            for command in Command::directions().chain(self.theseus_diagonals()) {
+                if let Some(next) = self.move_target(pos, command) {
+                    if seen.insert(next) {
+                        queue.push_back((next, steps + 1));
+                    }
+                }
+            }
+        }
+        None
+    }
+    /// The goals Theseus still has to get to: the one goal, or with several
+    /// the ones not visited yet
+    fn goals_to_reach(&self) -> &[Position] {
+        if self.multiple_goals {
+            &self.goals_left
+        } else {
+            std::slice::from_ref(&self.goal)
+        }
+    }
+    /// Returns whether a Minotaur is one step up, down, left or right of
+    /// Theseus. Diagonal neighbours and a Minotaur in his cell don't count.
+    pub fn minotaur_adjacent(&self) -> bool {
+        self.minotaurs
+            .iter()
+            .any(|&minotaur| self.distance(self.theseus, minotaur) == 1)
+    }
+}
+
+// Level editing
+impl Game {
+    /// Replaces the tile at `(row, col)` like [`Grid::set`], keeping the board
+    /// playable: walls can't go under Theseus or a Minotaur, and goals
+    /// still to visit can't be removed or added to. There can be only one
+    /// key and one door, and portals can't be added or removed.
+    pub fn set_cell(&mut self, row: usize, col: usize, cell: char) -> Result<(), BoardError> {
+        let pos = Position::new(row, col);
+        if cell == 'X' && (self.theseus == pos || self.minotaurs.contains(&pos)) {
+            return Err(BoardError::Occupied { row, col });
+        }
+        let goals = self.goals_to_reach();
+        if cell == 'G' && !goals.contains(&pos) {
+            return Err(BoardError::MultipleGoal);
+        }
+        if cell != 'G' && goals.contains(&pos) {
+            return Err(BoardError::NoGoal);
+        }
+        let elsewhere = |tile: Cell| {
+            let width = self.grid.width;
+            (0..self.grid.cells.len())
+                .any(|i| self.grid.cells[i] == tile && (i / width, i % width) != (row, col))
+        };
+        if cell == 'K' && elsewhere(Cell::Key) {
+            return Err(BoardError::MultipleKey);
+        }
+        if cell == 'D' && elsewhere(Cell::Door) {
+            return Err(BoardError::MultipleDoor);
+        }
+        // Portals are paired when the board is parsed
+        if cell == 'P' || self.grid.is_portal(row, col) {
+            return Err(BoardError::UnpairedPortal);
+        }
+        self.grid.set(row, col, cell)
+    }
+
+    /// Returns a copy of the game that starts with Theseus and a single
+    /// Minotaur at the given cells instead. The copy keeps the board and
+    /// settings, but starts over at turn 0 with no history.
+    pub fn with_positions(
+        &self,
+        theseus: (usize, usize),
+        minotaur: (usize, usize),
+    ) -> Result<Game, BoardError> {
+        for (row, col) in [theseus, minotaur] {
+            if !self.grid.in_bounds(row, col) {
+                return Err(BoardError::OutOfBounds { row, col });
+            }
+            if self.grid.is_wall(row, col) {
+                return Err(BoardError::Occupied { row, col });
+            }
+        }
+        let mut game = self.clone();
+        game.initial = State {
+            theseus: theseus.into(),
+            minotaurs: vec![minotaur.into()],
+            ..self.initial.clone()
+        };
+        game.reset();
+        Ok(game)
+    }
+}
+
+// JSON interchange
+impl Game {
+    /// Writes the game as a small JSON object: the board size, the tiles as
+    /// one string per row (entities left out), and where everything stands.
+    /// With several goals, the ones still to visit are written as
+    /// `goals_left`. Read it back with [`Game::from_json`].
+    pub fn to_json(&self) -> String {
+        let pos = |p: Position| format!("[{},{}]", p.row, p.col);
+        let rows: Vec<String> = Vec::from(self.grid.clone())
+            .iter()
+            .map(|row| format!("\"{}\"", row))
+            .collect();
+        let minotaurs: Vec<String> = self.minotaurs.iter().map(|&m| pos(m)).collect();
+        let goals_left = if self.multiple_goals {
+            let goals: Vec<String> = self.goals_left.iter().map(|&g| pos(g)).collect();
+            format!(",\"goals_left\":[{}]", goals.join(","))
+        } else {
+            String::new()
+        };
+        format!(
+            concat!(
+                "{{\"width\":{},\"height\":{},\"rows\":[{}],",
+                "\"theseus\":{},\"minotaurs\":[{}],\"goal\":{},",
+                "\"has_key\":{},\"turn\":{}{}}}"
+            ),
+            self.width(),
+            self.height(),
+            rows.join(","),
+            pos(self.theseus),
+            minotaurs.join(","),
+            pos(self.goal),
+            self.has_key,
+            self.turn,
+            goals_left,
+        )
+    }
+
+    /// Reads a game written by [`Game::to_json`], with the default rules.
+    /// The goal is taken from the rows; `has_key` and `turn` may be left
+    /// out, and so may `goals_left`, in which case every goal is still to
+    /// visit. The game resets to the position it was loaded in.
+    pub fn from_json(s: &str) -> Result<Game, BoardError> {
+        Game::from_json_with_config(s, GameConfig::default())
+    }
+
+    /// Like [`Game::from_json`], with the rules set by `config` instead of
+    /// the defaults
+    pub fn from_json_with_config(s: &str, config: GameConfig) -> Result<Game, BoardError> {
+        let json = Json::parse(s).ok_or(BoardError::InvalidJson)?;
+        let field = |name: &str| json.get(name).ok_or(BoardError::InvalidJson);
+        let rows: Vec<String> = field("rows")?
+            .as_array()
+            .and_then(|rows| {
+                rows.iter()
+                    .map(|row| Some(row.as_str()?.to_string()))
+                    .collect()
+            })
+            .ok_or(BoardError::InvalidJson)?;
+        let grid = Grid::try_from(rows)?;
+        if grid.cells.is_empty() {
+            return Err(BoardError::EmptyBoard);
+        }
+        if grid.width > config.max_dimension || grid.height > config.max_dimension {
+            let (width, height) = (grid.width, grid.height);
+            return Err(BoardError::TooLarge { width, height });
+        }
+
+        let theseus = field("theseus")?
+            .as_position()
+            .ok_or(BoardError::InvalidJson)?;
+        let minotaurs: Vec<Position> = field("minotaurs")?
+            .as_array()
+            .and_then(|all| all.iter().map(Json::as_position).collect())
+            .ok_or(BoardError::InvalidJson)?;
+        if minotaurs.is_empty() {
+            return Err(BoardError::NoMinotaur);
+        }
+        for &Position { row, col } in std::iter::once(&theseus).chain(&minotaurs) {
+            if !grid.in_bounds(row, col) {
+                return Err(BoardError::OutOfBounds { row, col });
+            }
+            if grid.is_wall(row, col) {
+                return Err(BoardError::Occupied { row, col });
+            }
+        }
+
+        let find = |cell: Cell| -> Vec<Position> {
+            let width = grid.width;
+            let at = move |i: usize| Position::new(i / width, i % width);
+            (0..grid.cells.len())
+                .filter(|&i| grid.cells[i] == cell)
+                .map(at)
+                .collect()
+        };
+        let goals = find(Cell::Goal);
+        if goals.is_empty() {
+            return Err(BoardError::NoGoal);
+        }
+        if goals.len() > 1 && !config.allow_multiple_goals {
+            return Err(BoardError::MultipleGoal);
+        }
+        if find(Cell::Key).len() > 1 {
+            return Err(BoardError::MultipleKey);
+        }
+        if find(Cell::Door).len() > 1 {
+            return Err(BoardError::MultipleDoor);
+        }
+        let portals = match find(Cell::Portal)[..] {
+            [] => None,
+            [a, b] => Some((a, b)),
+            _ => return Err(BoardError::UnpairedPortal),
+        };
+
+        let has_key = match json.get("has_key") {
+            Some(has_key) => has_key.as_bool().ok_or(BoardError::InvalidJson)?,
+            None => false,
+        };
+        let turn = match json.get("turn") {
+            Some(turn) => turn.as_number().ok_or(BoardError::InvalidJson)?,
+            None => 0,
+        };
+        let goals_left = match json.get("goals_left") {
+            Some(left) if config.allow_multiple_goals => left
+                .as_array()
+                .and_then(|all| {
+                    all.iter()
+                        .map(Json::as_position)
+                        .collect::<Option<Vec<_>>>()
+                })
+                .filter(|left| left.iter().all(|goal| goals.contains(goal)))
+                .ok_or(BoardError::InvalidJson)?,
+            _ => goals.clone(),
+        };
+        let mut game = Game::assemble(grid, theseus, minotaurs, goals, portals, has_key, &config)?;
+        if config.allow_multiple_goals {
+            game.goals_left.clone_from(&goals_left);
+            game.initial.goals_left = goals_left;
+        }
+        game.turn = u32::try_from(turn).map_err(|_| BoardError::InvalidJson)?;
+        Ok(game)
+    }
+}
+
+/// Just enough JSON for [`Game::from_json`]: no floats, negative numbers,
+/// or `null`
+enum Json {
+    Bool(bool),
+    Number(u64),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    /// Parses a whole document, or `None` if it isn't one
+    fn parse(s: &str) -> Option<Json> {
+        let mut chars = s.chars().peekable();
+        let value = Json::parse_value(&mut chars)?;
+        Json::skip_whitespace(&mut chars);
+        chars.peek().is_none().then_some(value)
+    }
+
+    fn skip_whitespace(chars: &mut std::iter::Peekable<std::str::Chars>) {
+        while chars.next_if(|ch| ch.is_whitespace()).is_some() {}
+    }
+
+    fn parse_value(chars: &mut std::iter::Peekable<std::str::Chars>) -> Option<Json> {
+        Json::skip_whitespace(chars);
+        match *chars.peek()? {
+            '{' => {
+                chars.next();
+                let mut fields = Vec::new();
+                Json::skip_whitespace(chars);
+                if chars.next_if_eq(&'}').is_some() {
+                    return Some(Json::Object(fields));
+                }
+                loop {
+                    Json::skip_whitespace(chars);
+                    let Json::String(name) = Json::parse_value(chars)? else {
+                        return None;
+                    };
+                    Json::skip_whitespace(chars);
+                    chars.next_if_eq(&':')?;
+                    fields.push((name, Json::parse_value(chars)?));
+                    Json::skip_whitespace(chars);
+                    match chars.next()? {
+                        ',' => continue,
+                        '}' => return Some(Json::Object(fields)),
+                        _ => return None,
+                    }
+                }
+            }
+            '[' => {
+                chars.next();
+                let mut items = Vec::new();
+                Json::skip_whitespace(chars);
+                if chars.next_if_eq(&']').is_some() {
+                    return Some(Json::Array(items));
+                }
+                loop {
+                    items.push(Json::parse_value(chars)?);
+                    Json::skip_whitespace(chars);
+                    match chars.next()? {
+                        ',' => continue,
+                        ']' => return Some(Json::Array(items)),
+                        _ => return None,
+                    }
+                }
+            }
+            '"' => {
+                chars.next();
+                let mut string = String::new();
+                loop {
+                    match chars.next()? {
+                        '"' => return Some(Json::String(string)),
+                        '\\' => match chars.next()? {
+                            'n' => string.push('\n'),
+                            't' => string.push('\t'),
+                            ch @ ('"' | '\\' | '/') => string.push(ch),
+                            _ => return None,
+                        },
+                        ch => string.push(ch),
+                    }
+                }
+            }
+            '0'..='9' => {
+                let mut digits = String::new();
+                while let Some(digit) = chars.next_if(char::is_ascii_digit) {
+                    digits.push(digit);
+                }
+                digits.parse().ok().map(Json::Number)
+            }
+            't' | 'f' => {
+                let mut word = String::new();
+                while let Some(ch) = chars.next_if(char::is_ascii_alphabetic) {
+                    word.push(ch);
+                }
+                match word.as_str() {
+                    "true" => Some(Json::Bool(true)),
+                    "false" => Some(Json::Bool(false)),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
+    fn get(&self, name: &str) -> Option<&Json> {
+        match self {
+            Json::Object(fields) => fields.iter().find(|(n, _)| n == name).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    fn as_bool(&self) -> Option<bool> {
+        match *self {
+            Json::Bool(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    fn as_number(&self) -> Option<u64> {
+        match *self {
+            Json::Number(n) => Some(n),
+            _ => None,
+        }
+    }
+
+    fn as_str(&self) -> Option<&str> {
+        match self {
+            Json::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    fn as_array(&self) -> Option<&[Json]> {
+        match self {
+            Json::Array(items) => Some(items),
+            _ => None,
+        }
+    }
+
+    /// Reads a `[row, col]` pair
+    fn as_position(&self) -> Option<Position> {
+        match self.as_array()? {
+            [row, col] => Some(Position::new(
+                usize::try_from(row.as_number()?).ok()?,
+                usize::try_from(col.as_number()?).ok()?,
+            )),
+            _ => None,
+        }
+    }
+}
+
+// Saved sessions
+impl Game {
+    /// Format version written as the first byte of a saved session
+    pub const SESSION_VERSION: u8 = 1;
+
+    /// Saves everything needed to carry on later: the board, the rules,
+    /// where everything stands, the turn, the visited cells and the undo
+    /// and redo history. Policies, the turn callback and the trace aren't
+    /// saved. Read it back with [`Game::load_session`].
+    pub fn save_session(&self) -> Vec<u8> {
+        let mut out = SessionWriter(vec![Game::SESSION_VERSION]);
+        out.usize(self.grid.width);
+        out.usize(self.grid.height);
+        for &cell in &self.grid.cells {
+            // Every tile character is ASCII.
+            out.u8(char::from(cell) as u8);
+        }
+        out.u32(self.minotaur_speed);
+        out.bool(self.max_turns.is_some());
+        out.u32(self.max_turns.unwrap_or(0));
+        out.bool(self.minotaur_axis_priority == Axis::VerticalFirst);
+        out.bool(self.wrap);
+        out.bool(self.diagonal_moves);
+        out.bool(self.multiple_goals);
+        out.bool(self.portals.is_some());
+        if let Some((a, b)) = self.portals {
+            out.position(a);
+            out.position(b);
+        }
+        out.u32(self.turn);
+        out.state(&self.state());
+        out.state(&self.initial);
+        out.usize(self.visited.len());
+        for &(row, col) in &self.visited {
+            out.position(Position::new(row, col));
+        }
+        out.usize(self.undo_stack.len());
+        for (state, added) in &self.undo_stack {
+            out.state(state);
+            out.bool(added.is_some());
+            out.position(added.map_or(Position::new(0, 0), Position::from));
+        }
+        out.usize(self.redo_stack.len());
+        for state in &self.redo_stack {
+            out.state(state);
+        }
+        out.0
+    }
+
+    /// Restores a game saved with [`Game::save_session`], with the default
+    /// policies. Like [`Game::from_json`], refuses anything standing in a
+    /// wall, and also a Minotaur speed over the number of cells.
+    pub fn load_session(bytes: &[u8]) -> Result<Game, BoardError> {
+        let mut input = SessionReader(bytes);
+        let version = input.u8()?;
+        if version != Game::SESSION_VERSION {
+            return Err(BoardError::UnknownSessionVersion(version));
+        }
+        let width = input.usize()?;
+        let height = input.usize()?;
+        let size = width
+            .checked_mul(height)
+            .ok_or(BoardError::InvalidSession)?;
+        if size == 0 {
+            return Err(BoardError::EmptyBoard);
+        }
+        if width > GameConfig::DEFAULT_MAX_DIMENSION || height > GameConfig::DEFAULT_MAX_DIMENSION {
+            return Err(BoardError::TooLarge { width, height });
+        }
+        let cells = (0..size)
+            .map(|_| {
+                Cell::try_from(char::from(input.u8()?)).map_err(|_| BoardError::InvalidSession)
+            })
+            .collect::<Result<Vec<Cell>, BoardError>>()?;
+        let grid = Grid::new(width, height, cells);
+
+        // More steps than the board has cells gets a Minotaur nowhere new,
+        // and a huge count would stall every turn
+        let minotaur_speed = input.u32()?;
+        if usize::try_from(minotaur_speed).map_or(true, |speed| speed > size) {
+            return Err(BoardError::InvalidSession);
+        }
+        let limited = input.bool()?;
+        let max_turns = limited.then_some(input.u32()?);
+        let minotaur_axis_priority = if input.bool()? {
+            Axis::VerticalFirst
+        } else {
+            Axis::HorizontalFirst
+        };
+        let wrap = input.bool()?;
+        let diagonal_moves = input.bool()?;
+        let multiple_goals = input.bool()?;
+        let portals = if input.bool()? {
+            Some((input.position(&grid)?, input.position(&grid)?))
+        } else {
+            None
+        };
+        let turn = input.u32()?;
+        let current = input.state(&grid)?;
+        let initial = input.state(&grid)?;
+        let visited = (0..input.len()?)
+            .map(|_| input.position(&grid).map(<(usize, usize)>::from))
+            .collect::<Result<HashSet<_>, _>>()?;
+        let undo_stack = (0..input.len()?)
+            .map(|_| {
+                let state = input.state(&grid)?;
+                let added = input.bool()?;
+                let cell = input.position(&grid)?;
+                Ok((state, added.then(|| cell.into())))
+            })
+            .collect::<Result<Vec<_>, BoardError>>()?;
+        let redo_stack = (0..input.len()?)
+            .map(|_| input.state(&grid))
+            .collect::<Result<Vec<_>, _>>()?;
+        if !input.0.is_empty() {
+            return Err(BoardError::InvalidSession);
+        }
+
+        Ok(Game {
+            grid,
+            theseus: current.theseus,
+            minotaurs: current.minotaurs,
+            goal: current.goal,
+            multiple_goals,
+            goals_left: current.goals_left,
+            has_key: current.has_key,
+            portals,
+            minotaur_speed,
+            minotaur_axis_priority,
+            wrap,
+            diagonal_moves,
+            turn,
+            max_turns,
+            minotaur_policy: default_policy(),
+            goal_policy: default_goal_policy(),
+            on_turn: None,
+            trace: None,
+            initial,
+            visited,
+            undo_stack,
+            redo_stack,
+            walls: None,
+        })
+    }
+}
+
+/// Writes the fields of a saved session, numbers little-endian
+struct SessionWriter(Vec<u8>);
+
+impl SessionWriter {
+    fn u8(&mut self, n: u8) {
+        self.0.push(n);
+    }
+
+    fn bool(&mut self, b: bool) {
+        self.u8(u8::from(b));
+    }
+
+    fn u32(&mut self, n: u32) {
+        self.0.extend_from_slice(&n.to_le_bytes());
+    }
+
+    fn usize(&mut self, n: usize) {
+        self.0.extend_from_slice(&(n as u64).to_le_bytes());
+    }
+
+    fn position(&mut self, pos: Position) {
+        self.usize(pos.row);
+        self.usize(pos.col);
+    }
+
+    fn positions(&mut self, all: &[Position]) {
+        self.usize(all.len());
+        for &pos in all {
+            self.position(pos);
+        }
+    }
+
+    fn state(&mut self, state: &State) {
+        self.position(state.theseus);
+        self.positions(&state.minotaurs);
+        self.bool(state.has_key);
+        self.position(state.goal);
+        self.positions(&state.goals_left);
+    }
+}
+
+/// Reads back what [`SessionWriter`] wrote, checking positions against
+/// the board as it goes
+struct SessionReader<'a>(&'a [u8]);
+
+impl SessionReader<'_> {
+    fn take<const N: usize>(&mut self) -> Result<[u8; N], BoardError> {
+        let (bytes, rest) = self
+            .0
+            .split_first_chunk::<N>()
+            .ok_or(BoardError::InvalidSession)?;
+        self.0 = rest;
+        Ok(*bytes)
+    }
+
+    fn u8(&mut self) -> Result<u8, BoardError> {
+        Ok(self.take::<1>()?[0])
+    }
+
+    fn bool(&mut self) -> Result<bool, BoardError> {
+        match self.u8()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(BoardError::InvalidSession),
+        }
+    }
+
+    fn u32(&mut self) -> Result<u32, BoardError> {
+        Ok(u32::from_le_bytes(self.take()?))
+    }
+
+    fn usize(&mut self) -> Result<usize, BoardError> {
+        usize::try_from(u64::from_le_bytes(self.take()?)).map_err(|_| BoardError::InvalidSession)
+    }
+
+    /// Reads a position, which has to be on the board
+    fn position(&mut self, grid: &Grid) -> Result<Position, BoardError> {
+        let row = self.usize()?;
+        let col = self.usize()?;
+        if !grid.in_bounds(row, col) {
+            return Err(BoardError::OutOfBounds { row, col });
+        }
+        Ok(Position::new(row, col))
+    }
+
+    /// Reads how many items follow. Each takes at least a byte, so a bad
+    /// count fails here instead of allocating far more than was read.
+    fn len(&mut self) -> Result<usize, BoardError> {
+        let len = self.usize()?;
+        if len > self.0.len() {
+            return Err(BoardError::InvalidSession);
+        }
+        Ok(len)
+    }
+
+    fn positions(&mut self, grid: &Grid) -> Result<Vec<Position>, BoardError> {
+        (0..self.len()?).map(|_| self.position(grid)).collect()
+    }
+
+    /// Reads a state, whose entities have to stand on open tiles
+    fn state(&mut self, grid: &Grid) -> Result<State, BoardError> {
+        let state = State {
+            theseus: self.position(grid)?,
+            minotaurs: self.positions(grid)?,
+            has_key: self.bool()?,
+            goal: self.position(grid)?,
+            goals_left: self.positions(grid)?,
+        };
+        state.check(grid)?;
+        Ok(state)
+    }
+}
+
+// Board transforms
+impl Game {
+    /// Returns the game turned a quarter turn clockwise
+    pub fn rotated_90(&self) -> Game {
+        let height = self.height();
+        self.transformed(true, |p| Position::new(p.col, height - 1 - p.row))
+    }
+
+    /// Returns the game turned half a turn
+    pub fn rotated_180(&self) -> Game {
+        let (width, height) = (self.width(), self.height());
+        self.transformed(false, |p| {
+            Position::new(height - 1 - p.row, width - 1 - p.col)
+        })
+    }
+
+    /// Returns the game turned a quarter turn anticlockwise
+    pub fn rotated_270(&self) -> Game {
+        let width = self.width();
+        self.transformed(true, |p| Position::new(width - 1 - p.col, p.row))
+    }
+
+    /// Returns the game flipped left to right
+    pub fn mirrored_horizontal(&self) -> Game {
+        let width = self.width();
+        self.transformed(false, |p| Position::new(p.row, width - 1 - p.col))
+    }
+
+    /// Returns the game flipped top to bottom
+    pub fn mirrored_vertical(&self) -> Game {
+        let height = self.height();
+        self.transformed(false, |p| Position::new(height - 1 - p.row, p.col))
+    }
+
+    /// Moves every cell of the game to where `map` sends it, history
+    /// included. Quarter turns swap the board's sides, so they also swap the
+    /// Minotaur's axis priority: it keeps chasing the same way relative to
+    /// the board, and the puzzle stays just as solvable.
+    fn transformed(&self, quarter_turn: bool, map: impl Fn(Position) -> Position) -> Game {
+        let (width, height) = if quarter_turn {
+            (self.height(), self.width())
+        } else {
+            (self.width(), self.height())
+        };
+        let mut cells = vec![Cell::Empty; width * height];
+        for (row, col) in self.positions() {
+            let to = map(Position::new(row, col));
+            cells[to.row * width + to.col] = self.grid.cells[row * self.width() + col];
+        }
+        let map_cell = |(row, col): (usize, usize)| {
+            let to = map(Position::new(row, col));
+            (to.row, to.col)
+        };
+        let map_state = |state: &State| State {
+            theseus: map(state.theseus),
+            minotaurs: state.minotaurs.iter().map(|&m| map(m)).collect(),
+            has_key: state.has_key,
+            goal: map(state.goal),
+            goals_left: state.goals_left.iter().map(|&g| map(g)).collect(),
+        };
+
+        let mut game = self.clone();
+        game.grid = Grid::new(width, height, cells);
+        // The goal is already on the new grid, so no `set_state` here
+        let state = map_state(&self.state());
+        game.theseus = state.theseus;
+        game.minotaurs = state.minotaurs;
+        game.goal = state.goal;
+        game.goals_left = state.goals_left;
+        game.portals = self.portals.map(|(a, b)| (map(a), map(b)));
+        game.initial = map_state(&self.initial);
+        game.visited = self.visited.iter().map(|&cell| map_cell(cell)).collect();
+        game.undo_stack = self
+            .undo_stack
+            .iter()
+            .map(|(state, added)| (map_state(state), added.map(map_cell)))
+            .collect();
+        game.redo_stack = self.redo_stack.iter().map(map_state).collect();
+        if quarter_turn {
+            game.minotaur_axis_priority = match self.minotaur_axis_priority {
+                Axis::HorizontalFirst => Axis::VerticalFirst,
+                Axis::VerticalFirst => Axis::HorizontalFirst,
+            };
+        }
+        game
+    }
+}
+
+// Board generation
+impl Game {
+    /// Generates a maze of the given size that Theseus can win, carved with a
+    /// recursive backtracker. The same seed always gives the same board.
+    ///
+    /// Panics if `width` or `height` is less than 5 or more than
+    /// [`GameConfig::DEFAULT_MAX_DIMENSION`].
+    pub fn generate(width: usize, height: usize, seed: u64) -> Game {
+        assert!(
+            width >= 5 && height >= 5,
+            "generated boards must be at least 5x5"
+        );
+        let max = GameConfig::DEFAULT_MAX_DIMENSION;
+        assert!(
+            width <= max && height <= max,
+            "generated boards must be at most {}x{}",
+            max,
+            max
+        );
+        let mut rng = Rng::new(seed);
+        loop {
+            let maze = carve_maze(width, height, &mut rng);
+            let mut open: Vec<usize> = (0..maze.len()).filter(|&i| maze[i] == ' ').collect();
+            // A few placements per maze before carving a fresh one
+            for _ in 0..8 {
+                // Partial Fisher-Yates: the first three cells become T, M and G
+                for i in 0..3 {
+                    let j = i + rng.below(open.len() - i);
+                    open.swap(i, j);
+                }
+                let mut cells = maze.clone();
+                cells[open[0]] = 'T';
+                cells[open[1]] = 'M';
+                cells[open[2]] = 'G';
+                let board: String = cells
+                    .chunks(width)
+                    .flat_map(|row| row.iter().copied().chain(std::iter::once('\n')))
+                    .collect();
+                let game = Game::from_board(&board).expect("generated board is valid");
+                if game.is_winnable() {
+                    return game;
+                }
+            }
+        }
+    }
+}
+
+/// Builds a [`Game`] tile by tile instead of from a board string
+#[derive(Clone, Debug, Default)]
+pub struct GameBuilder {
+    width: usize,
+    height: usize,
+    walls: Vec<Position>,
+    theseus: Vec<Position>,
+    minotaurs: Vec<Position>,
+    goals: Vec<Position>,
+}
+
+impl GameBuilder {
+    /// Starts a `width` by `height` board of empty tiles
+    pub fn new(width: usize, height: usize) -> Self {
+        GameBuilder {
+            width,
+            height,
+            ..GameBuilder::default()
+        }
+    }
+    pub fn set_wall(mut self, row: usize, col: usize) -> Self {
+        self.walls.push(Position::new(row, col));
+        self
+    }
+    pub fn theseus(mut self, row: usize, col: usize) -> Self {
+        self.theseus.push(Position::new(row, col));
+        self
+    }
+    /// Adds a Minotaur; call again for more than one
+    pub fn minotaur(mut self, row: usize, col: usize) -> Self {
+        self.minotaurs.push(Position::new(row, col));
+        self
+    }
+    pub fn goal(mut self, row: usize, col: usize) -> Self {
+        self.goals.push(Position::new(row, col));
+        self
+    }
+
+    /// Builds the game, checking it the same way [`Game::from_board`] does.
+    /// Placing two things, other than two walls, on one tile is
+    /// `BoardError::Occupied`.
+    pub fn build(&self) -> Result<Game, BoardError> {
+        let max = GameConfig::DEFAULT_MAX_DIMENSION;
+        if self.width > max || self.height > max {
+            let (width, height) = (self.width, self.height);
+            return Err(BoardError::TooLarge { width, height });
+        }
+        let mut cells = vec![' '; self.width * self.height];
+        let walls = self.walls.iter().map(|&pos| (pos, 'X'));
+        let theseus = self.theseus.iter().map(|&pos| (pos, 'T'));
+        let minotaurs = self.minotaurs.iter().map(|&pos| (pos, 'M'));
+        let goals = self.goals.iter().map(|&pos| (pos, 'G'));
+        for (pos, ch) in walls.chain(theseus).chain(minotaurs).chain(goals) {
+            let Position { row, col } = pos;
+            if row >= self.height || col >= self.width {
+                return Err(BoardError::OutOfBounds { row, col });
+            }
+            let cell = &mut cells[row * self.width + col];
+            if *cell != ' ' && !(*cell == 'X' && ch == 'X') {
+                return Err(BoardError::Occupied { row, col });
+            }
+            *cell = ch;
+        }
+        let board: String = cells
+            .chunks(self.width.max(1))
+            .flat_map(|row| row.iter().copied().chain(std::iter::once('\n')))
+            .collect();
+        Game::from_board(&board)
+    }
+}
+
+/// Carves a perfect maze into a `width` by `height` block of walls, returned
+/// row by row as board characters. Rooms sit at odd coordinates, so an even
+/// width or height just leaves an extra wall along the far edge.
+fn carve_maze(width: usize, height: usize, rng: &mut Rng) -> Vec<char> {
+    let mut cells = vec!['X'; width * height];
+    let idx = |p: Position| p.row * width + p.col;
+    let is_room =
+        |p: Position| p.row % 2 == 1 && p.col % 2 == 1 && p.row < height - 1 && p.col < width - 1;
+
+    let start = Position::new(1, 1);
+    cells[idx(start)] = ' ';
+    let mut stack = vec![start];
+    while let Some(&room) = stack.last() {
+        let unvisited: Vec<Position> = Command::directions()
+            .filter_map(|command| {
+                let (dr, dc) = command.delta();
+                room.offset(2 * dr, 2 * dc)
+            })
+            .filter(|&next| is_room(next) && cells[idx(next)] == 'X')
+            .collect();
+        if unvisited.is_empty() {
+            stack.pop();
+            continue;
+        }
+        let next = unvisited[rng.below(unvisited.len())];
+        // Knock down the wall between the two rooms
+        let between = Position::new((room.row + next.row) / 2, (room.col + next.col) / 2);
+        cells[idx(between)] = ' ';
+        cells[idx(next)] = ' ';
+        stack.push(next);
+    }
+    cells
+}
+
+/// Small seeded generator (SplitMix64), so boards are reproducible without
+/// pulling in a dependency.
+#[derive(Debug)]
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        Rng(seed)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a number in `0..n`. `n` must be non-zero.
+    fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+
+    /// Returns true with probability `p`
+    fn chance(&mut self, p: f64) -> bool {
+        // The top 53 bits give every f64 in [0, 1) with the same spacing
+        let unit = (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64;
+        unit < p
+    }
+}
+
+/// Walks back from `state` to `start` along every recorded parent, adding
+/// each full path to `solutions` in playing order; `path` holds the commands
+/// after `state`, last first. Stops once there are `limit` solutions.
+fn collect_paths(
+    state: &State,
+    start: &State,
+    parents: &HashMap<State, Vec<(State, Command)>>,
+    path: &mut Vec<Command>,
+    solutions: &mut Vec<Vec<Command>>,
+    limit: usize,
+) {
+    if solutions.len() >= limit {
+        return;
+    }
+    if state == start {
+        solutions.push(path.iter().rev().copied().collect());
+        return;
+    }
+    for (parent, command) in &parents[state] {
+        path.push(*command);
+        collect_paths(parent, start, parents, path, solutions, limit);
+        path.pop();
+    }
+}
+
+/// Returns the Manhattan (L1) distance between two positions
+pub fn manhattan(a: Position, b: Position) -> usize {
+    a.row.abs_diff(b.row) + a.col.abs_diff(b.col)
+}
+
+/// Returns the Chebyshev (L∞) distance between two positions, the number of
+/// king moves between them
+pub fn chebyshev(a: Position, b: Position) -> usize {
+    a.row.abs_diff(b.row).max(a.col.abs_diff(b.col))
+}
+
+/// Decides where a Minotaur moves each step. A move into a wall or off the
+/// board leaves the Minotaur where it is.
+pub trait MinotaurPolicy: Send + Sync {
+    /// Picks the move for the Minotaur standing at `minotaur`
+    fn choose(&self, game: &Game, minotaur: Position) -> Command;
+}
+
+/// The classic Minotaur. It closes the horizontal gap to Theseus if it can,
+/// otherwise the vertical gap, and otherwise waits. With
+/// [`Axis::VerticalFirst`] it tries the vertical gap first instead.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct GreedyPolicy;
+
+impl MinotaurPolicy for GreedyPolicy {
+    fn choose(&self, game: &Game, minotaur: Position) -> Command {
+        let (dr, dc) = game.gap(minotaur, game.theseus_position());
+
+        // 1) Try horizontal move that decreases |tx - mx|
+        let horizontal = match dc.cmp(&0) {
+            Ordering::Less => Some(Command::Left),
+            Ordering::Greater => Some(Command::Right),
+            Ordering::Equal => None,
+        };
+        // 2) Otherwise, try vertical move that decreases |ty - my|
+        let vertical = match dr.cmp(&0) {
+            Ordering::Less => Some(Command::Up),
+            Ordering::Greater => Some(Command::Down),
+            Ordering::Equal => None,
+        };
+        let order = match game.minotaur_axis_priority() {
+            Axis::HorizontalFirst => [horizontal, vertical],
+            Axis::VerticalFirst => [vertical, horizontal],
+        };
+        // 3) Else: don't move
+        order
+            .into_iter()
+            .flatten()
+            .find(|&command| game.move_target(minotaur, command).is_some())
+            .unwrap_or(Command::Skip)
+    }
+}
+
+/// A tougher Minotaur that moves in all eight directions. It takes the step
+/// that brings it closest to Theseus by Chebyshev distance, breaking ties by
+/// Manhattan distance, and waits if no step gets it any closer.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DiagonalPolicy;
+
+impl MinotaurPolicy for DiagonalPolicy {
+    fn choose(&self, game: &Game, minotaur: Position) -> Command {
+        let theseus = game.theseus_position();
+        let distance = |pos: Position| {
+            let (dr, dc) = game.gap(pos, theseus);
+            let (dr, dc) = (dr.unsigned_abs(), dc.unsigned_abs());
+            (dr.max(dc), dr + dc)
+        };
+        Command::directions()
+            .chain(Command::diagonals())
+            .filter_map(|command| Some((command, game.move_target(minotaur, command)?)))
+            .filter(|&(_, next)| distance(next) < distance(minotaur))
+            .min_by_key(|&(_, next)| distance(next))
+            .map_or(Command::Skip, |(command, _)| command)
+    }
+}
+
+/// An easier Minotaur that chases Theseus like [`GreedyPolicy`], but now and
+/// then blunders into a random step instead (or waits). Seeded, so the same
+/// seed always makes the same mistakes. Whether it blunders depends only on
+/// the seed and where Theseus and the Minotaur stand, so looking ahead with
+/// [`Game::hint`] or the solvers doesn't change how it plays.
+#[derive(Clone, Copy, Debug)]
+pub struct ClumsyPolicy {
+    /// How likely each move is to be a mistake, from 0 (never) to 1
+    pub mistake_probability: f64,
+    seed: u64,
+}
+
+impl ClumsyPolicy {
+    /// A Minotaur that blunders with probability `mistake_probability`,
+    /// drawing its mistakes from `seed`
+    pub fn new(mistake_probability: f64, seed: u64) -> Self {
+        ClumsyPolicy {
+            mistake_probability,
+            seed,
+        }
+    }
+}
+
+/// Never makes a mistake
+impl Default for ClumsyPolicy {
+    fn default() -> Self {
+        ClumsyPolicy::new(0.0, 0)
+    }
+}
+
+impl MinotaurPolicy for ClumsyPolicy {
+    fn choose(&self, game: &Game, minotaur: Position) -> Command {
+        let theseus = game.theseus_position();
+        let key = [theseus.row, theseus.col, minotaur.row, minotaur.col]
+            .into_iter()
+            .fold(self.seed, |key, n| Rng::new(key ^ n as u64).next_u64());
+        let mut rng = Rng::new(key);
+        if !rng.chance(self.mistake_probability) {
+            return GreedyPolicy.choose(game, minotaur);
+        }
+        let moves: Vec<Command> = Command::all()
+            .filter(|&command| game.move_target(minotaur, command).is_some())
+            .collect();
+        moves[rng.below(moves.len())]
+    }
+}
+
+/// A Minotaur for stealth boards. It chases Theseus like [`GreedyPolicy`]
+/// only while it can see him, and otherwise stays where it is.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct LineOfSightPolicy {
+    /// How many cells away the Minotaur can see, if limited
+    pub range: Option<usize>,
+}
+
+impl LineOfSightPolicy {
+    /// Returns whether a Minotaur at `minotaur` can see Theseus: he's in the
+    /// same row or column, within range, with no wall in between
+    pub fn sees(&self, game: &Game, minotaur: Position) -> bool {
+        let theseus = game.theseus_position();
+        let distance = manhattan(minotaur, theseus);
+        if self.range.is_some_and(|range| distance > range) {
+            return false;
+        }
+        let grid = game.grid();
+        if minotaur.row == theseus.row {
+            let (from, to) = (minotaur.col.min(theseus.col), minotaur.col.max(theseus.col));
+            (from + 1..to).all(|col| !grid.is_wall(theseus.row, col))
+        } else if minotaur.col == theseus.col {
+            let (from, to) = (minotaur.row.min(theseus.row), minotaur.row.max(theseus.row));
+            (from + 1..to).all(|row| !grid.is_wall(row, theseus.col))
+        } else {
+            false
+        }
+    }
+}
+
+impl MinotaurPolicy for LineOfSightPolicy {
+    fn choose(&self, game: &Game, minotaur: Position) -> Command {
+        if self.sees(game, minotaur) {
+            GreedyPolicy.choose(game, minotaur)
+        } else {
+            Command::Skip
+        }
     }
 }
 
+fn default_policy() -> Arc<dyn MinotaurPolicy> {
+    Arc::new(GreedyPolicy)
+}
+
+/// Decides where the goal moves at the end of each turn. The goal only moves
+/// onto empty tiles; any other move leaves it where it is.
+pub trait GoalPolicy: Send + Sync {
+    /// Picks the move for the goal standing at `goal`
+    fn choose(&self, game: &Game, goal: Position) -> Command;
+}
+
+/// The classic goal, which never moves
+#[derive(Clone, Copy, Debug, Default)]
+pub struct StationaryGoal;
+
+impl GoalPolicy for StationaryGoal {
+    fn choose(&self, _game: &Game, _goal: Position) -> Command {
+        Command::Skip
+    }
+}
+
+fn default_goal_policy() -> Arc<dyn GoalPolicy> {
+    Arc::new(StationaryGoal)
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Command {
     /// Move one tile up
     Up,
@@ -296,24 +3614,363 @@ pub enum Command {
     Right,
     /// Don't move at all
     Skip,
+    /// Move one tile diagonally up and to the left
+    UpLeft,
+    /// Move one tile diagonally up and to the right
+    UpRight,
+    /// Move one tile diagonally down and to the left
+    DownLeft,
+    /// Move one tile diagonally down and to the right
+    DownRight,
+}
+
+/// Writes the command's name, which parses back into the same command
+impl Display for Command {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Command::Up => "Up",
+            Command::Down => "Down",
+            Command::Left => "Left",
+            Command::Right => "Right",
+            Command::Skip => "Skip",
+            Command::UpLeft => "UpLeft",
+            Command::UpRight => "UpRight",
+            Command::DownLeft => "DownLeft",
+            Command::DownRight => "DownRight",
+        };
+        f.write_str(name)
+    }
+}
+
+impl Command {
+    /// Returns the `(row, col)` offset the command moves by
+    pub fn delta(&self) -> (isize, isize) {
+        match self {
+            Command::Up => (-1, 0),
+            Command::Down => (1, 0),
+            Command::Left => (0, -1),
+            Command::Right => (0, 1),
+            Command::Skip => (0, 0),
+            Command::UpLeft => (-1, -1),
+            Command::UpRight => (-1, 1),
+            Command::DownLeft => (1, -1),
+            Command::DownRight => (1, 1),
+        }
+    }
+
+    /// Iterates over the four movement commands: Up, Down, Left, Right
+    pub fn directions() -> impl Iterator<Item = Command> {
+        [Command::Up, Command::Down, Command::Left, Command::Right].into_iter()
+    }
+
+    /// Iterates over the four diagonal commands: UpLeft, UpRight, DownLeft,
+    /// DownRight
+    pub fn diagonals() -> impl Iterator<Item = Command> {
+        [
+            Command::UpLeft,
+            Command::UpRight,
+            Command::DownLeft,
+            Command::DownRight,
+        ]
+        .into_iter()
+    }
+
+    /// Returns whether this is one of the [`Command::diagonals`]
+    pub fn is_diagonal(&self) -> bool {
+        Command::diagonals().any(|diagonal| diagonal == *self)
+    }
+
+    /// Iterates over every command of classic play: the four directions,
+    /// then Skip. Diagonals aren't included.
+    pub fn all() -> impl Iterator<Item = Command> {
+        Command::directions().chain(std::iter::once(Command::Skip))
+    }
+
+    /// Returns the command that moves back the way this one came
+    pub fn opposite(&self) -> Command {
+        match self {
+            Command::Up => Command::Down,
+            Command::Down => Command::Up,
+            Command::Left => Command::Right,
+            Command::Right => Command::Left,
+            Command::Skip => Command::Skip,
+            Command::UpLeft => Command::DownRight,
+            Command::UpRight => Command::DownLeft,
+            Command::DownLeft => Command::UpRight,
+            Command::DownRight => Command::UpLeft,
+        }
+    }
+}
+
+/// Error returned when a string isn't a recognized movement command
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParseCommandError(pub String);
+impl Display for ParseCommandError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Unknown command: {}", self.0)
+    }
+}
+impl Error for ParseCommandError {}
+
+impl FromStr for Command {
+    type Err = ParseCommandError;
+
+    /// Parses a movement token, ignoring case and surrounding whitespace.
+    /// The escape sequences terminals send for the arrow keys (`"\x1b[A"`
+    /// and so on, or `"\x1bOA"` in application mode) count as moves too.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let token = s.trim();
+        match token.to_lowercase().as_str() {
+            "w" | "up" | "\x1b[a" | "\x1boa" => Ok(Command::Up),
+            "s" | "down" | "\x1b[b" | "\x1bob" => Ok(Command::Down),
+            "a" | "left" | "\x1b[d" | "\x1bod" => Ok(Command::Left),
+            "d" | "right" | "\x1b[c" | "\x1boc" => Ok(Command::Right),
+            "wait" | "skip" | "." => Ok(Command::Skip),
+            // `q` already quits, so diagonals get two-letter shorthands
+            "ul" | "upleft" => Ok(Command::UpLeft),
+            "ur" | "upright" => Ok(Command::UpRight),
+            "dl" | "downleft" => Ok(Command::DownLeft),
+            "dr" | "downright" => Ok(Command::DownRight),
+            _ => Err(ParseCommandError(token.to_string())),
+        }
+    }
+}
+
+/// Outcome of reading one line of player input
+#[derive(Clone, Debug, PartialEq)]
+pub enum InputResult {
+    /// A movement command
+    Move(Command),
+    /// The player asked to leave (`q`, `quit` or `exit`)
+    Quit,
+    /// The trimmed line wasn't a recognized command
+    Invalid(String),
+    /// The input was closed before a line could be read
+    Eof,
+}
+
+/// Reads one line of input and classifies it. Unlike [`input`], this tells
+/// quitting, end of input, and unrecognized text apart.
+///
+/// Arrow keys work, but since the terminal is line-buffered the player still
+/// has to press Enter after each one.
+#[cfg(feature = "std-io")]
+pub fn read_input(mut stdin: impl io::BufRead) -> InputResult {
+    let mut line = String::new();
+    // A failed read leaves nothing more to read, so treat it like EOF.
+    match stdin.read_line(&mut line) {
+        Ok(0) | Err(_) => return InputResult::Eof,
+        Ok(_) => {}
+    }
+    parse_input_line(&line)
+}
+
+/// Classifies one line read by [`read_input`] or [`input_async`]
+#[cfg(any(feature = "std-io", feature = "async"))]
+fn parse_input_line(line: &str) -> InputResult {
+    let token = line.trim();
+    // A bare Enter waits a turn
+    if token.is_empty() {
+        return InputResult::Move(Command::Skip);
+    }
+    match token.to_lowercase().as_str() {
+        "q" | "quit" | "exit" => InputResult::Quit,
+        _ => match token.parse() {
+            Ok(command) => InputResult::Move(command),
+            Err(_) => InputResult::Invalid(token.to_string()),
+        },
+    }
+}
+
+/// Parses a whitespace-separated script of commands such as `"d d s w skip"`,
+/// accepting the same spellings as [`input`]. The error names the first
+/// token that isn't a command and its index in the script.
+pub fn parse_commands(input: &str) -> Result<Vec<Command>, String> {
+    input
+        .split_whitespace()
+        .enumerate()
+        .map(|(i, token)| {
+            token
+                .parse()
+                .map_err(|e: ParseCommandError| format!("{} (token {})", e, i))
+        })
+        .collect()
+}
+
+/// Writes commands as a compact replay string, one character each: `u`,
+/// `d`, `l`, `r` for the four directions, `.` for `Skip`, and the numeric
+/// keypad digits `7`, `9`, `1`, `3` for the diagonals. Read it back with
+/// [`decode_transcript`].
+pub fn encode_transcript(commands: &[Command]) -> String {
+    commands
+        .iter()
+        .map(|command| match command {
+            Command::Up => 'u',
+            Command::Down => 'd',
+            Command::Left => 'l',
+            Command::Right => 'r',
+            Command::Skip => '.',
+            Command::UpLeft => '7',
+            Command::UpRight => '9',
+            Command::DownLeft => '1',
+            Command::DownRight => '3',
+        })
+        .collect()
+}
+
+/// Reads a replay string written by [`encode_transcript`]. The error names
+/// the first character that isn't a command and its index.
+pub fn decode_transcript(transcript: &str) -> Result<Vec<Command>, String> {
+    transcript
+        .chars()
+        .enumerate()
+        .map(|(i, ch)| match ch {
+            'u' => Ok(Command::Up),
+            'd' => Ok(Command::Down),
+            'l' => Ok(Command::Left),
+            'r' => Ok(Command::Right),
+            '.' => Ok(Command::Skip),
+            '7' => Ok(Command::UpLeft),
+            '9' => Ok(Command::UpRight),
+            '1' => Ok(Command::DownLeft),
+            '3' => Ok(Command::DownRight),
+            _ => Err(format!("Unknown command: {} (char {})", ch, i)),
+        })
+        .collect()
+}
+
+/// Plays `game` to the end: shows the board on `writer`, reads a command from
+/// `reader`, and plays it as a full turn with [`Game::step`], until the game
+/// is won or lost or the player quits or closes the input. Returns the status
+/// the game was left in, `Continue` if the player stopped early.
+///
+/// Output is best effort; a failing `writer` doesn't stop the game.
+#[cfg(feature = "std-io")]
+pub fn run_game<R: io::BufRead, W: io::Write>(
+    game: &mut Game,
+    mut reader: R,
+    writer: &mut W,
+) -> GameStatus {
+    loop {
+        let message = match game.status() {
+            GameStatus::Win => "You win!",
+            GameStatus::Lose => "You lose!",
+            GameStatus::Continue => "",
+        };
+        let _ = writeln!(writer, "{}{}", game, message);
+        if game.status() != GameStatus::Continue {
+            return game.status();
+        }
+        match read_input(&mut reader) {
+            InputResult::Move(command) => {
+                game.step(command);
+            }
+            InputResult::Quit | InputResult::Eof => return game.status(),
+            InputResult::Invalid(line) => {
+                let _ = writeln!(writer, "Unknown command: {}", line);
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std-io")]
+pub fn input(stdin: impl io::BufRead) -> Option<Command> {
+    // On EOF, quit, or bad input, return None (signals invalid/quit to caller loop).
+    match read_input(stdin) {
+        InputResult::Move(command) => Some(command),
+        InputResult::Quit | InputResult::Invalid(_) | InputResult::Eof => None,
+    }
+}
+
+/// Like [`input`], but reads the line without blocking, for frontends
+/// running on tokio
+#[cfg(feature = "async")]
+pub async fn input_async<R: tokio::io::AsyncBufRead + Unpin>(reader: &mut R) -> Option<Command> {
+    use tokio::io::AsyncBufReadExt;
+    let mut line = String::new();
+    match reader.read_line(&mut line).await {
+        Ok(0) | Err(_) => None,
+        Ok(_) => match parse_input_line(&line) {
+            InputResult::Move(command) => Some(command),
+            InputResult::Quit | InputResult::Invalid(_) | InputResult::Eof => None,
+        },
+    }
 }
 
-pub fn input(stdin: impl io::Read + io::BufRead) -> Option<Command> {
-    // Read one line. On EOF, return None (signals invalid/quit to caller loop).
-    let mut reader = io::BufReader::new(stdin);
+/// Reads one line of input and parses each character of it as its own
+/// command, so `"ddsw"` is Right, Right, Down, Up. Whitespace and characters
+/// that aren't commands are skipped, and `q` drops itself and everything
+/// after it. An arrow key's escape sequence is read whole, as one command.
+/// Returns no commands at end of input.
+#[cfg(feature = "std-io")]
+pub fn input_batch(mut stdin: impl io::BufRead) -> Vec<Command> {
     let mut line = String::new();
-    if reader.read_line(&mut line).ok()? == 0 {
-        return None;
-    }
-    // Normalize
-    let s = line.trim().to_lowercase();
-    match s.as_str() {
-        "w" | "up" => Some(Command::Up),
-        "s" | "down" => Some(Command::Down),
-        "a" | "left" => Some(Command::Left),
-        "d" | "right" => Some(Command::Right),
-        "" | "wait" | "skip" | "." => Some(Command::Skip),
-        "q" | "quit" | "exit" => None,
-        _ => None,
+    if stdin.read_line(&mut line).is_err() {
+        return Vec::new();
+    }
+    let mut commands = Vec::new();
+    let mut chars = line.chars().peekable();
+    while let Some(ch) = chars.next() {
+        let mut token = ch.to_string();
+        // An arrow key arrives as ESC, then `[` or `O`, then a letter
+        if ch == '\x1b' {
+            if let Some(kind) = chars.next_if(|&kind| kind == '[' || kind == 'O') {
+                token.push(kind);
+                token.extend(chars.next());
+            }
+        } else if ch.is_whitespace() {
+            continue;
+        } else if ch.eq_ignore_ascii_case(&'q') {
+            break;
+        }
+        commands.extend(token.parse::<Command>().ok());
+    }
+    commands
+}
+
+/// Bindings for playing in a browser through `wasm-bindgen`. The page plays
+/// one game at a time.
+#[cfg(target_arch = "wasm32")]
+pub mod wasm {
+    use super::{Command, Game, ParseCommandError};
+    use std::cell::RefCell;
+    use wasm_bindgen::prelude::*;
+
+    thread_local! {
+        static GAME: RefCell<Option<Game>> = const { RefCell::new(None) };
+    }
+
+    /// Starts a new game from a board in the usual text format
+    #[wasm_bindgen]
+    pub fn new_game(board: &str) -> Result<(), JsValue> {
+        let game = Game::from_board(board).map_err(|e| JsValue::from_str(&e.to_string()))?;
+        GAME.with(|cell| *cell.borrow_mut() = Some(game));
+        Ok(())
+    }
+
+    /// Plays one turn of the current game and returns the status: `"Win"`,
+    /// `"Lose"` or `"Continue"`
+    #[wasm_bindgen]
+    pub fn step(cmd: &str) -> Result<String, JsValue> {
+        let command: Command = cmd
+            .parse()
+            .map_err(|e: ParseCommandError| JsValue::from_str(&e.to_string()))?;
+        with_game(|game| game.step(command).to_string())
+    }
+
+    /// Renders the current game, as [`Game::render`] does
+    #[wasm_bindgen]
+    pub fn render() -> Result<String, JsValue> {
+        with_game(|game| game.render())
+    }
+
+    fn with_game<T>(f: impl FnOnce(&mut Game) -> T) -> Result<T, JsValue> {
+        GAME.with(|cell| {
+            let mut game = cell.borrow_mut();
+            let game = game
+                .as_mut()
+                .ok_or_else(|| JsValue::from_str("No game started"))?;
+            Ok(f(game))
+        })
     }
 }
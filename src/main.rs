@@ -1,4 +1,4 @@
-use std::io::BufReader;
+use std::io::{BufReader, IsTerminal};
 use theseus::*;
 
 fn wait() {
@@ -6,7 +6,12 @@ fn wait() {
 }
 
 fn show_with_message(game: &Game, message: &str) -> Result<(), Box<dyn std::error::Error>> {
-    game.show();
+    // Colors are only for an interactive terminal; NO_COLOR turns them off.
+    if std::io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none() {
+        print!("{}", game.render_ansi());
+    } else {
+        game.show();
+    }
     println!("{}", message);
     Ok(())
 }
@@ -14,11 +19,11 @@ fn show_with_message(game: &Game, message: &str) -> Result<(), Box<dyn std::erro
 fn game_over(game: &Game) -> Result<bool, Box<dyn std::error::Error>> {
     match game.status() {
         GameStatus::Win => {
-            show_with_message(&game, "You win!")?;
+            show_with_message(game, "You win!")?;
             Ok(true)
         }
         GameStatus::Lose => {
-            show_with_message(&game, "You lose!")?;
+            show_with_message(game, "You lose!")?;
             Ok(true)
         }
         GameStatus::Continue => Ok(false),
@@ -32,10 +37,10 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         std::process::exit(1);
     }
     let board_path = std::env::args().nth(1).unwrap();
-    let board = std::fs::read_to_string(board_path).expect("Cannot find board file");
 
     // Initialize game struct
-    let mut game = Game::from_board(&board)?;
+    let mut game = Game::from_file(board_path)?;
+    game.set_minotaur_speed(2);
 
     // Game loop
     loop {
@@ -43,10 +48,11 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
         // read user input
         let cmd = loop {
-            match input(BufReader::new(std::io::stdin())) {
-                Some(cmd) => break cmd,
-                None => {
-                    show_with_message(&game, "Invalid command. Please try again.")?;
+            match read_input(BufReader::new(std::io::stdin())) {
+                InputResult::Move(cmd) => break cmd,
+                InputResult::Quit | InputResult::Eof => return Ok(()),
+                InputResult::Invalid(line) => {
+                    show_with_message(&game, &format!("Unknown command: {}", line))?;
                 }
             }
         };
@@ -57,22 +63,15 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             break;
         }
 
-        show_with_message(&game, "Minotaurs turn 1...")?;
-        wait();
-
-        game.minotaur_move();
-
-        if game_over(&game)? {
-            break;
-        }
-
-        show_with_message(&game, "Minotaurs turn 2...")?;
-        wait();
+        for step in 1..=game.minotaur_speed() {
+            show_with_message(&game, &format!("Minotaurs turn {}...", step))?;
+            wait();
 
-        game.minotaur_move();
+            game.minotaur_move();
 
-        if game_over(&game)? {
-            break;
+            if game_over(&game)? {
+                return Ok(());
+            }
         }
     }
 
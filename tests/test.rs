@@ -43,6 +43,7 @@ fn check_board_matches_game(board: &str, game: &theseus::Game) {
     }
 }
 
+#[allow(clippy::expect_fun_call)]
 fn check_board(board: &str) {
     let game = theseus::Game::from_board(board)
         .expect(&format!("Failed to create game from board {}", board));
@@ -78,6 +79,7 @@ fn test_load_board_30() {
     check_board(board2);
 }
 
+#[allow(clippy::expect_fun_call)]
 fn check_theseus_move(start_board: &str, command: theseus::Command, finish_board: &str) {
     let mut game = theseus::Game::from_board(start_board)
         .expect(&format!("Failed to create game from board {}", start_board));
@@ -170,6 +172,12 @@ fn test_theseus_move_disallowed_5() {
 }
 
 #[test]
+#[allow(
+    clippy::expect_fun_call,
+    clippy::needless_borrow,
+    clippy::useless_vec,
+    unused_variables
+)]
 fn test_minotaur_move_basic_10() {
     let boards = vec![
         "XXXXXXXXX\n\
@@ -213,6 +221,7 @@ fn test_minotaur_move_basic_10() {
 }
 
 #[test]
+#[allow(clippy::expect_fun_call, clippy::needless_borrow)]
 fn test_minotaur_move_disallowed_10() {
     {
         let board = "XXXXXXX\n\
@@ -248,6 +257,7 @@ fn test_minotaur_move_disallowed_10() {
 }
 
 #[test]
+#[allow(clippy::expect_fun_call)]
 fn test_status_win_4() {
     let board = "XXXXXX\n\
                  X    X\n\
@@ -263,6 +273,7 @@ fn test_status_win_4() {
 }
 
 #[test]
+#[allow(clippy::expect_fun_call)]
 fn test_status_lose_3() {
     let board = "XXXXXX\n\
                  X    X\n\
@@ -278,6 +289,7 @@ fn test_status_lose_3() {
 }
 
 #[test]
+#[allow(clippy::expect_fun_call)]
 fn test_status_continue_3() {
     let board = "XXXXXX\n\
                  X    X\n\
@@ -291,3 +303,3729 @@ fn test_status_continue_3() {
     game.minotaur_move();
     assert_eq!(game.status(), theseus::GameStatus::Continue);
 }
+
+#[test]
+fn test_solve_one_move() {
+    let board = "XXXXXX\n\
+                 XM TGX\n\
+                 XXXXXX\n";
+    let game = theseus::Game::from_board(board)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+    assert_eq!(game.solve(), Some(vec![theseus::Command::Right]));
+}
+
+#[test]
+fn test_solve_requires_skip() {
+    let board = "XXXXXXXXX\n\
+                 X  M    X\n\
+                 X X    XX\n\
+                 X   XX XX\n\
+                 X T  G  X\n\
+                 XX   X XX\n\
+                 XXXXXXXXX\n";
+    let mut game = theseus::Game::from_board(board)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+    game.set_minotaur_speed(2);
+    let solution = game.solve().expect("Expected board to be solvable");
+    assert_eq!(
+        solution,
+        vec![
+            theseus::Command::Skip,
+            theseus::Command::Right,
+            theseus::Command::Right,
+            theseus::Command::Right,
+        ]
+    );
+}
+
+#[test]
+fn test_solve_unwinnable() {
+    let board = "XXXXXXX\n\
+                 XT M XX\n\
+                 XXXXXGX\n\
+                 XXXXXXX\n";
+    let game = theseus::Game::from_board(board)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+    assert_eq!(game.solve(), None);
+}
+
+#[test]
+fn test_positions() {
+    let board = "XXXXXXXXX\n\
+                 X  T  XXX\n\
+                 X XXX XXX\n\
+                 X   X  GX\n\
+                 X XXX XXX\n\
+                 X  M  XXX\n\
+                 XXXXXXXXX\n";
+    let game = theseus::Game::from_board(board)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+    assert_eq!(game.theseus_position(), theseus::Position::new(1, 3));
+    assert_eq!(game.minotaur_position(), theseus::Position::new(5, 3));
+    assert_eq!(game.goal_position(), theseus::Position::new(3, 7));
+
+    let (row, col) = game.theseus_position().into();
+    assert!(game.is_theseus(row, col));
+    let (row, col) = game.minotaur_position().into();
+    assert!(game.is_minotaur(row, col));
+    let (row, col) = game.goal_position().into();
+    assert!(game.is_goal(row, col));
+}
+
+#[test]
+fn test_render_wall_glyph() {
+    let board = "XXXX\n\
+                 XMTX\n\
+                 X GX\n\
+                 XXXX\n";
+    let game = theseus::Game::from_board(board)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+    let rendered = game.render();
+    assert_eq!(rendered.lines().count(), board.lines().count());
+    for (expected, actual) in board.lines().zip(rendered.lines()) {
+        let actual: Vec<char> = actual.chars().collect();
+        assert_eq!(actual.len(), expected.chars().count());
+        for (cell, glyph) in expected.chars().zip(actual) {
+            if cell == 'X' {
+                assert_eq!(glyph as u32, 0x2588);
+            } else {
+                assert_eq!(glyph, cell);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_show_to_writer() {
+    let board = "XXXXXX\n\
+                 XM TGX\n\
+                 XXXXXX\n";
+    let game = theseus::Game::from_board(board)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+    let mut out: Vec<u8> = Vec::new();
+    game.show_to(&mut out).expect("Failed to write to buffer");
+    let out = String::from_utf8(out).expect("Rendered board is not UTF-8");
+    assert_eq!(out, game.render());
+    assert!(out.ends_with("\u{2588}\n"));
+}
+
+#[test]
+fn test_minotaur_speed_second_step_capture() {
+    let board = "XXXXXXX\n\
+                 XM T GX\n\
+                 XXXXXXX\n";
+    let mut game = theseus::Game::from_board(board)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+    assert_eq!(game.minotaur_speed(), 1);
+    assert_eq!(game.minotaur_turn(), theseus::GameStatus::Continue);
+    assert_eq!(game.minotaur_position(), theseus::Position::new(1, 2));
+
+    let mut game = theseus::Game::from_board(board)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+    game.set_minotaur_speed(2);
+    assert_eq!(game.minotaur_turn(), theseus::GameStatus::Lose);
+    assert_eq!(game.minotaur_position(), theseus::Position::new(1, 3));
+}
+
+#[test]
+fn test_minotaur_move_reports_movement() {
+    let board = "XXXXXXX\n\
+                 XT   GX\n\
+                 XXXXX X\n\
+                 XXXMX X\n\
+                 XXXXXXX\n";
+    let mut game = theseus::Game::from_board(board)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+    assert!(!game.minotaur_move());
+    check_board_matches_game(board, &game);
+
+    let board = "XXXXXXX\n\
+                 XT   GX\n\
+                 X     X\n\
+                 X  M  X\n\
+                 XXXXXXX\n";
+    let mut game = theseus::Game::from_board(board)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+    assert!(game.minotaur_move());
+    assert_eq!(game.minotaur_position(), theseus::Position::new(3, 2));
+    assert!(game.minotaur_move());
+    assert!(game.minotaur_move());
+    assert_eq!(game.minotaur_position(), theseus::Position::new(2, 1));
+}
+
+#[test]
+fn test_command_from_str() {
+    use theseus::Command;
+    let cases = [
+        ("w", Command::Up),
+        ("up", Command::Up),
+        ("s", Command::Down),
+        ("down", Command::Down),
+        ("a", Command::Left),
+        ("left", Command::Left),
+        ("d", Command::Right),
+        ("right", Command::Right),
+        (".", Command::Skip),
+        ("wait", Command::Skip),
+        ("skip", Command::Skip),
+        (" UP\n", Command::Up),
+        ("Right", Command::Right),
+    ];
+    for (token, command) in cases {
+        assert_eq!(token.parse::<Command>(), Ok(command), "token {:?}", token);
+    }
+    for token in ["q", "quit", "exit", "jump", ""] {
+        assert!(token.parse::<Command>().is_err(), "token {:?}", token);
+    }
+    assert_eq!(
+        " jump ".parse::<Command>(),
+        Err(theseus::ParseCommandError("jump".to_string()))
+    );
+}
+
+#[cfg(feature = "std-io")]
+#[test]
+fn test_read_input_outcomes() {
+    use theseus::{read_input, Command, InputResult};
+    assert_eq!(read_input(&b"d\n"[..]), InputResult::Move(Command::Right));
+    assert_eq!(read_input(&b"\n"[..]), InputResult::Move(Command::Skip));
+    assert_eq!(read_input(&b"Quit\n"[..]), InputResult::Quit);
+    assert_eq!(read_input(&b"q"[..]), InputResult::Quit);
+    assert_eq!(
+        read_input(&b"  foo \n"[..]),
+        InputResult::Invalid("foo".to_string())
+    );
+    assert_eq!(read_input(&b""[..]), InputResult::Eof);
+
+    assert_eq!(theseus::input(&b"w\n"[..]), Some(Command::Up));
+    assert_eq!(theseus::input(&b"quit\n"[..]), None);
+    assert_eq!(theseus::input(&b"foo\n"[..]), None);
+    assert_eq!(theseus::input(&b""[..]), None);
+}
+
+#[test]
+fn test_from_board_sized_pads() {
+    let board = "XXXX\n\
+                 XTM\n\
+                 XG";
+    let game = theseus::Game::from_board_sized(board, 5, 4)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+    let padded = "XXXX \n\
+                  XTM  \n\
+                  XG   \n\
+                  \x20    \n";
+    check_board_matches_game(padded, &game);
+}
+
+#[test]
+fn test_from_board_sized_rejects_oversize() {
+    let board = "XXXX\n\
+                 XTMX\n\
+                 XG X\n";
+    assert!(matches!(
+        theseus::Game::from_board_sized(board, 3, 3),
+        Err(theseus::BoardError::InvalidSize)
+    ));
+    assert!(matches!(
+        theseus::Game::from_board_sized(board, 4, 2),
+        Err(theseus::BoardError::InvalidSize)
+    ));
+    assert!(matches!(
+        theseus::Game::from_board_sized("TMGT", 4, 1),
+        Err(theseus::BoardError::MultipleTheseus)
+    ));
+    assert!(theseus::Game::from_board_sized(board, 4, 3).is_ok());
+}
+
+#[test]
+fn test_dimensions() {
+    let board = "XXXXXXXXX\n\
+                 X  T  XXX\n\
+                 X XXX XXX\n\
+                 X   X  GX\n\
+                 X XXX XXX\n\
+                 X  M  XXX\n\
+                 XXXXXXXXX\n";
+    let game = theseus::Game::from_board(board)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+    assert_eq!(game.width(), 9);
+    assert_eq!(game.height(), 7);
+
+    let grid = theseus::Grid::new(3, 2, vec![theseus::Cell::Empty; 6]);
+    assert_eq!(grid.width(), 3);
+    assert_eq!(grid.height(), 2);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde_round_trip() {
+    use theseus::Command;
+    let board = "XXXXXXXXX\n\
+                 X  T  XXX\n\
+                 X XXX XXX\n\
+                 X   X  GX\n\
+                 X XXX XXX\n\
+                 X  M  XXX\n\
+                 XXXXXXXXX\n";
+    let mut game = theseus::Game::from_board(board)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+    game.set_minotaur_speed(2);
+
+    let json = serde_json::to_string(&game).expect("Failed to serialize game");
+    // The grid is stored as one string per row, without the entities
+    assert!(json.contains("\"X     XXX\""));
+    let mut restored: theseus::Game =
+        serde_json::from_str(&json).expect("Failed to deserialize game");
+    assert_eq!(restored.minotaur_speed(), 2);
+
+    let commands = [
+        Command::Right,
+        Command::Right,
+        Command::Down,
+        Command::Down,
+        Command::Right,
+    ];
+    for command in commands {
+        for game in [&mut game, &mut restored] {
+            game.theseus_move(command);
+            game.minotaur_turn();
+        }
+        assert_eq!(game.render(), restored.render());
+        assert_eq!(game.status(), restored.status());
+    }
+
+    let json = serde_json::to_string(&Command::Left).expect("Failed to serialize command");
+    assert_eq!(
+        serde_json::from_str::<Command>(&json).ok(),
+        Some(Command::Left)
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde_rejects_bad_games() {
+    use serde_json::{json, Value};
+    let board = "XXXXXXXXX\n\
+                 X  T  XXX\n\
+                 X XXX XXX\n\
+                 X   X  GX\n\
+                 X XXX XXX\n\
+                 X  M  XXX\n\
+                 XXXXXXXXX\n";
+    let game = theseus::Game::from_board(board)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+    let value = serde_json::to_value(&game).expect("Failed to serialize game");
+    let load = |value: &Value| serde_json::from_value::<theseus::Game>(value.clone());
+    assert!(load(&value).is_ok());
+
+    let edits = [
+        ("minotaurs", json!([])),
+        ("goal", json!({ "row": 30, "col": 1 })),
+        ("goal", json!({ "row": 1, "col": 1 })),
+        ("theseus", json!({ "row": 0, "col": 0 })),
+        ("grid", json!([])),
+    ];
+    for (field, bad) in edits {
+        let mut edited = value.clone();
+        edited[field] = bad;
+        assert!(
+            load(&edited).is_err(),
+            "Accepted a game with {} edited",
+            field
+        );
+    }
+    let mut edited = value.clone();
+    edited["initial"]["minotaurs"] = json!([{ "row": 2, "col": 2 }]);
+    assert!(load(&edited).is_err());
+}
+
+#[test]
+fn test_to_board_round_trip() {
+    let board = "XXXXXXXXX\n\
+                 X  T  XXX\n\
+                 X XXX XXX\n\
+                 X   X  GX\n\
+                 X XXX XXX\n\
+                 X  M  XXX\n\
+                 XXXXXXXXX\n";
+    let mut game = theseus::Game::from_board(board)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+    assert_eq!(game.to_board(), board);
+
+    game.theseus_move(theseus::Command::Right);
+    game.minotaur_move();
+    let expected = "XXXXXXXXX\n\
+                    X   T XXX\n\
+                    X XXX XXX\n\
+                    X   X  GX\n\
+                    X XXX XXX\n\
+                    X   M XXX\n\
+                    XXXXXXXXX\n";
+    assert_eq!(game.to_board(), expected);
+    let reloaded = theseus::Game::from_board(&game.to_board())
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", expected));
+    check_board_matches_game(expected, &reloaded);
+    assert_eq!(reloaded.to_board(), expected);
+}
+
+#[test]
+fn test_cell_char_conversion() {
+    use theseus::Cell;
+    for (cell, ch) in [(Cell::Wall, 'X'), (Cell::Empty, ' '), (Cell::Goal, 'G')] {
+        assert_eq!(char::from(cell), ch);
+        assert_eq!(Cell::try_from(ch), Ok(cell));
+    }
+    assert_eq!(Cell::try_from('T'), Err(theseus::ParseCellError('T')));
+
+    let grid = theseus::Grid::new(2, 1, vec![Cell::Wall, Cell::Goal]);
+    assert_eq!(grid.get(0, 1), Some(Cell::Goal));
+    assert_eq!(grid.get_char(0, 0), Some('X'));
+    assert_eq!(grid.get_char(1, 0), None);
+}
+
+#[test]
+fn test_multiple_minotaurs_converge() {
+    let boards = [
+        "XXXXXXXXX\n\
+         XM  T  MX\n\
+         X      GX\n\
+         XXXXXXXXX\n",
+        "XXXXXXXXX\n\
+         X M T M X\n\
+         X      GX\n\
+         XXXXXXXXX\n",
+        "XXXXXXXXX\n\
+         X  MTM  X\n\
+         X      GX\n\
+         XXXXXXXXX\n",
+    ];
+    let mut game = theseus::Game::from_board(boards[0])
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", boards[0]));
+    assert_eq!(
+        game.minotaur_positions(),
+        &[theseus::Position::new(1, 1), theseus::Position::new(1, 7)]
+    );
+    check_board_matches_game(boards[0], &game);
+    for finish_board in boards.iter().skip(1) {
+        assert!(game.minotaur_move());
+        check_board_matches_game(finish_board, &game);
+        assert_eq!(game.status(), theseus::GameStatus::Continue);
+    }
+
+    // Either Minotaur reaching Theseus ends the game
+    assert!(game.minotaur_move());
+    assert!(game.is_theseus(1, 4));
+    assert!(game.is_minotaur(1, 4));
+    assert_eq!(game.status(), theseus::GameStatus::Lose);
+}
+
+#[test]
+fn test_multiple_minotaurs_strict() {
+    let board = "XXXXXX\n\
+                 XMTMGX\n\
+                 XXXXXX\n";
+    assert!(theseus::Game::from_board(board).is_ok());
+    assert!(matches!(
+        theseus::Game::from_board_strict(board),
+        Err(theseus::BoardError::MultipleMinotaur)
+    ));
+    assert!(theseus::Game::from_board_strict("XMTGX").is_ok());
+}
+
+#[test]
+fn test_undo_redo() {
+    let board = "XXXXXXX\n\
+                 XM  T X\n\
+                 X    GX\n\
+                 XXXXXXX\n";
+    let mut game = theseus::Game::from_board(board)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+    assert!(!game.undo());
+    assert!(!game.redo());
+
+    game.theseus_move(theseus::Command::Right);
+    game.minotaur_move();
+    game.minotaur_move();
+    let after_first = "XXXXXXX\n\
+                       X  M TX\n\
+                       X    GX\n\
+                       XXXXXXX\n";
+    check_board_matches_game(after_first, &game);
+
+    game.theseus_move(theseus::Command::Down);
+    game.minotaur_move();
+    let after_second = "XXXXXXX\n\
+                        X   M X\n\
+                        X    TX\n\
+                        XXXXXXX\n";
+    check_board_matches_game(after_second, &game);
+
+    // Undo takes back the Minotaur steps along with Theseus's move
+    assert!(game.undo());
+    check_board_matches_game(after_first, &game);
+    assert!(game.undo());
+    check_board_matches_game(board, &game);
+    assert!(!game.undo());
+
+    assert!(game.redo());
+    check_board_matches_game(after_first, &game);
+
+    // A new move discards the rest of the redo history
+    game.theseus_move(theseus::Command::Skip);
+    assert!(!game.redo());
+    assert!(game.undo());
+    check_board_matches_game(after_first, &game);
+}
+
+#[test]
+fn test_step_win_on_move() {
+    // The Minotaur is next to the goal, but Theseus gets there first
+    let board = "XXXXX\n\
+                 XTGMX\n\
+                 XXXXX\n";
+    let mut game = theseus::Game::from_board(board)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+    assert_eq!(game.step(theseus::Command::Right), theseus::GameStatus::Win);
+    assert_eq!(game.minotaur_position(), theseus::Position::new(1, 3));
+}
+
+#[test]
+fn test_step_capture_on_minotaur_move() {
+    let board = "XXXXXXX\n\
+                 XM T GX\n\
+                 XXXXXXX\n";
+    let mut game = theseus::Game::from_board(board)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+    assert_eq!(game.step(theseus::Command::Left), theseus::GameStatus::Lose);
+    assert!(game.is_minotaur(1, 2));
+    assert!(game.is_theseus(1, 2));
+
+    let mut game = theseus::Game::from_board(board)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+    assert_eq!(
+        game.step(theseus::Command::Skip),
+        theseus::GameStatus::Continue
+    );
+    assert!(game.undo());
+    check_board_matches_game(board, &game);
+}
+
+#[test]
+fn test_step_after_game_over() {
+    use theseus::{Command, GameStatus, Position};
+    let board = "XXXXXXXX\n\
+                 XMT  G X\n\
+                 XXXXXXXX\n";
+    let mut game = theseus::Game::from_board(board)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+    assert_eq!(game.step(Command::Left), GameStatus::Lose);
+    let turns = game.turns();
+
+    // A lost game stays lost: Theseus can't walk off the Minotaur to the goal
+    for _ in 0..3 {
+        assert_eq!(game.step(Command::Right), GameStatus::Lose);
+    }
+    assert_eq!(game.theseus_position(), Position::new(1, 1));
+    assert_eq!(game.turns(), turns);
+    let outcome = game.apply(Command::Right);
+    assert!(!outcome.theseus_moved && !outcome.minotaur_moved);
+    assert_eq!(outcome.status, GameStatus::Lose);
+}
+
+#[test]
+fn test_capture_ends_minotaur_turn() {
+    // The first Minotaur catches Theseus on its first sub-step, so neither
+    // Minotaur gets a second one.
+    let board = "XXXXXXXXXX\n\
+                 XGMT    MX\n\
+                 XXXXXXXXXX\n";
+    let mut game = theseus::Game::from_board(board)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+    game.set_minotaur_speed(2);
+    assert_eq!(game.minotaur_turn(), theseus::GameStatus::Lose);
+    assert_eq!(
+        game.minotaur_positions(),
+        &[theseus::Position::new(1, 3), theseus::Position::new(1, 8)]
+    );
+
+    // Theseus steps onto the goal and wins before the Minotaur can follow
+    let board = "XXXXXXX\n\
+                 XM TG X\n\
+                 XXXXXXX\n";
+    let mut game = theseus::Game::from_board(board)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+    game.set_minotaur_speed(2);
+    assert_eq!(game.step(theseus::Command::Right), theseus::GameStatus::Win);
+    assert_eq!(game.minotaur_position(), theseus::Position::new(1, 1));
+}
+
+#[test]
+fn test_is_winnable() {
+    let board = "XXXXXXXXX\n\
+                 X  T  XXX\n\
+                 X XXX XXX\n\
+                 X   X  GX\n\
+                 X XXX XXX\n\
+                 X  M  XXX\n\
+                 XXXXXXXXX\n";
+    let game = theseus::Game::from_board(board)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+    assert!(game.is_winnable());
+
+    // The goal is walled off
+    let board = "XXXXXXX\n\
+                 XT M XX\n\
+                 XXXXXGX\n\
+                 XXXXXXX\n";
+    let game = theseus::Game::from_board(board)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+    assert!(!game.is_winnable());
+
+    // Theseus is boxed in next to the Minotaur
+    let board = "XXXXX\n\
+                 XXXGX\n\
+                 XTMXX\n\
+                 XXXXX\n";
+    let game = theseus::Game::from_board(board)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+    assert!(!game.is_winnable());
+}
+
+#[test]
+fn test_custom_minotaur_policy() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    // Always tries to move up, and counts how often it was asked
+    struct UpPolicy(Arc<AtomicUsize>);
+    impl theseus::MinotaurPolicy for UpPolicy {
+        fn choose(&self, _game: &theseus::Game, _minotaur: theseus::Position) -> theseus::Command {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            theseus::Command::Up
+        }
+    }
+
+    let board = "XXXXXXX\n\
+                 X    GX\n\
+                 X     X\n\
+                 XM  T X\n\
+                 XXXXXXX\n";
+    let mut game = theseus::Game::from_board(board)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+    let calls = Arc::new(AtomicUsize::new(0));
+    game.set_minotaur_policy(UpPolicy(Arc::clone(&calls)));
+
+    assert!(game.minotaur_move());
+    assert_eq!(game.minotaur_position(), theseus::Position::new(2, 1));
+    assert!(game.minotaur_move());
+    assert_eq!(game.minotaur_position(), theseus::Position::new(1, 1));
+    // Blocked by the wall, so it stays put
+    assert!(!game.minotaur_move());
+    assert_eq!(game.minotaur_position(), theseus::Position::new(1, 1));
+    assert_eq!(calls.load(Ordering::SeqCst), 3);
+}
+
+#[test]
+fn test_command_delta_and_opposite() {
+    use theseus::Command;
+    let cases = [
+        (Command::Up, (-1, 0), Command::Down),
+        (Command::Down, (1, 0), Command::Up),
+        (Command::Left, (0, -1), Command::Right),
+        (Command::Right, (0, 1), Command::Left),
+        (Command::Skip, (0, 0), Command::Skip),
+    ];
+    for (command, delta, opposite) in cases {
+        assert_eq!(command.delta(), delta);
+        assert_eq!(command.opposite(), opposite);
+        assert_eq!(command.opposite().opposite(), command);
+        let (dr, dc) = command.opposite().delta();
+        assert_eq!((dr, dc), (-delta.0, -delta.1));
+    }
+}
+
+#[test]
+fn test_command_iterators() {
+    use theseus::Command;
+    let directions: Vec<Command> = Command::directions().collect();
+    assert_eq!(
+        directions,
+        vec![Command::Up, Command::Down, Command::Left, Command::Right]
+    );
+    let all: Vec<Command> = Command::all().collect();
+    assert_eq!(all.len(), 5);
+    assert_eq!(&all[..4], &directions[..]);
+    assert_eq!(all[4], Command::Skip);
+}
+
+#[test]
+fn test_legal_theseus_moves() {
+    use theseus::Command;
+    let board = "XXXXX\n\
+                 XT  X\n\
+                 X   X\n\
+                 XM GX\n\
+                 XXXXX\n";
+    let game = theseus::Game::from_board(board)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+    assert_eq!(
+        game.legal_theseus_moves(),
+        vec![Command::Down, Command::Right, Command::Skip]
+    );
+
+    let board = "XXXXX\n\
+                 X   X\n\
+                 X T X\n\
+                 XM GX\n\
+                 XXXXX\n";
+    let game = theseus::Game::from_board(board)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+    assert_eq!(
+        game.legal_theseus_moves(),
+        Command::all().collect::<Vec<_>>()
+    );
+
+    // The board edge blocks moves just like a wall
+    let board = "T \n\
+                 MG\n";
+    let game = theseus::Game::from_board(board)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+    assert_eq!(
+        game.legal_theseus_moves(),
+        vec![Command::Down, Command::Right, Command::Skip]
+    );
+}
+
+#[test]
+fn test_manhattan_distance() {
+    assert_eq!(theseus::manhattan((2, 3).into(), (2, 3).into()), 0);
+    assert_eq!(theseus::manhattan((2, 3).into(), (2, 4).into()), 1);
+    assert_eq!(theseus::manhattan((2, 3).into(), (1, 3).into()), 1);
+    assert_eq!(theseus::manhattan((2, 3).into(), (3, 4).into()), 2);
+    assert_eq!(theseus::manhattan((0, 5).into(), (4, 1).into()), 8);
+
+    let board = "XXXXXX\n\
+                 XMT GX\n\
+                 X   MX\n\
+                 XXXXXX\n";
+    let mut game = theseus::Game::from_board(board)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+    assert_eq!(game.theseus_minotaur_distance(), 1);
+    game.minotaur_move();
+    assert_eq!(game.theseus_minotaur_distance(), 0);
+
+    let board = "XXXXXX\n\
+                 X T GX\n\
+                 X   MX\n\
+                 XXXXXX\n";
+    let game = theseus::Game::from_board(board)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+    assert_eq!(game.theseus_minotaur_distance(), 3);
+}
+
+#[test]
+fn test_generate() {
+    let game = theseus::Game::generate(11, 9, 42);
+    assert_eq!(game.width(), 11);
+    assert_eq!(game.height(), 9);
+    assert!(game.is_winnable());
+    assert_eq!(game.minotaur_positions().len(), 1);
+
+    let board = game.to_board();
+    assert_eq!(theseus::Game::generate(11, 9, 42).to_board(), board);
+    assert_ne!(theseus::Game::generate(11, 9, 43).to_board(), board);
+    for ch in ['T', 'M', 'G'] {
+        assert_eq!(board.chars().filter(|&c| c == ch).count(), 1);
+    }
+    // The outer edge is always wall
+    assert!(board.lines().next().unwrap().chars().all(|c| c == 'X'));
+
+    for seed in 0..20 {
+        assert!(theseus::Game::generate(6, 5, seed).is_winnable());
+    }
+}
+
+#[test]
+fn test_difficulty() {
+    let straight = "XXXXXXXX\n\
+                    XM T  GX\n\
+                    XXXXXXXX\n";
+    let straight = theseus::Game::from_board(straight)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", straight));
+    assert_eq!(straight.difficulty(), Some(3));
+
+    let wait = "XXXXXXXXX\n\
+                X  M    X\n\
+                X X    XX\n\
+                X   XX XX\n\
+                X T  G  X\n\
+                XX   X XX\n\
+                XXXXXXXXX\n";
+    let mut wait = theseus::Game::from_board(wait)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", wait));
+    wait.set_minotaur_speed(2);
+    let score = wait.difficulty().expect("Expected board to be solvable");
+    assert!(score > straight.difficulty().unwrap());
+    assert!(score > wait.solve().unwrap().len() as u32);
+
+    // With a wall above Theseus, bumping into it mustn't stand in for the wait
+    let walled = "XXXXXXXXX\n\
+                  X  M    X\n\
+                  X X    XX\n\
+                  X X XX XX\n\
+                  X T  G  X\n\
+                  XX   X XX\n\
+                  XXXXXXXXX\n";
+    let mut walled = theseus::Game::from_board(walled)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", walled));
+    walled.set_minotaur_speed(2);
+    assert_eq!(
+        walled.solve().expect("Expected board to be solvable")[0],
+        theseus::Command::Skip
+    );
+    assert_eq!(walled.difficulty(), Some(7));
+
+    let sealed = "XXXXXXX\n\
+                  XT M XX\n\
+                  XXXXXGX\n\
+                  XXXXXXX\n";
+    let sealed = theseus::Game::from_board(sealed)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", sealed));
+    assert_eq!(sealed.difficulty(), None);
+}
+
+#[test]
+fn test_hint() {
+    // Moving left walks into the Minotaur; only Down leads to the goal
+    let board = "XXXXXX\n\
+                 XMT XX\n\
+                 XX XXX\n\
+                 XX  GX\n\
+                 XXXXXX\n";
+    let game = theseus::Game::from_board(board)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+    assert_eq!(game.hint(), Some(theseus::Command::Down));
+
+    let board = "XXXXXXXXX\n\
+                 X  M    X\n\
+                 X X    XX\n\
+                 X   XX XX\n\
+                 X T  G  X\n\
+                 XX   X XX\n\
+                 XXXXXXXXX\n";
+    let mut game = theseus::Game::from_board(board)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+    game.set_minotaur_speed(2);
+    assert_eq!(game.hint(), Some(theseus::Command::Skip));
+    game.step(theseus::Command::Skip);
+    assert_eq!(game.hint(), Some(theseus::Command::Right));
+
+    // No hint once the game is over
+    game.step(theseus::Command::Right);
+    game.step(theseus::Command::Right);
+    assert_eq!(game.step(theseus::Command::Right), theseus::GameStatus::Win);
+    assert_eq!(game.hint(), None);
+
+    // Beside a wall the hint is still to wait, not to walk into the wall
+    let board = "XXXXXXXXX\n\
+                 X  M    X\n\
+                 X X    XX\n\
+                 X X XX XX\n\
+                 X T  G  X\n\
+                 XX   X XX\n\
+                 XXXXXXXXX\n";
+    let mut game = theseus::Game::from_board(board)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+    game.set_minotaur_speed(2);
+    assert_eq!(game.hint(), Some(theseus::Command::Skip));
+}
+
+#[test]
+fn test_turn_counter() {
+    let board = "XXXXXXXXX\n\
+                 X  T  XXX\n\
+                 X XXX XXX\n\
+                 X   X  GX\n\
+                 X XXX XXX\n\
+                 X  M  XXX\n\
+                 XXXXXXXXX\n";
+    let mut game = theseus::Game::from_board(board)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+    assert_eq!(game.turns(), 0);
+    game.step(theseus::Command::Right);
+    game.step(theseus::Command::Skip);
+    game.theseus_move(theseus::Command::Left);
+    game.minotaur_move();
+    assert_eq!(game.turns(), 3);
+
+    assert!(game.undo());
+    assert!(game.undo());
+    assert_eq!(game.turns(), 1);
+    assert!(game.redo());
+    assert_eq!(game.turns(), 2);
+}
+
+#[test]
+fn test_max_turns() {
+    let board = "XXXXXXXX\n\
+                 XM T  GX\n\
+                 XXXXXXXX\n";
+    let mut game = theseus::Game::from_board(board)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+    assert_eq!(game.max_turns(), None);
+    game.set_max_turns(Some(4));
+    assert_eq!(
+        game.step(theseus::Command::Right),
+        theseus::GameStatus::Continue
+    );
+    assert_eq!(
+        game.step(theseus::Command::Right),
+        theseus::GameStatus::Continue
+    );
+    assert_eq!(game.step(theseus::Command::Right), theseus::GameStatus::Win);
+
+    let mut game = theseus::Game::from_board(board)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+    game.set_max_turns(Some(3));
+    assert_eq!(
+        game.step(theseus::Command::Skip),
+        theseus::GameStatus::Continue
+    );
+    assert_eq!(
+        game.step(theseus::Command::Right),
+        theseus::GameStatus::Continue
+    );
+    assert_eq!(
+        game.step(theseus::Command::Right),
+        theseus::GameStatus::Lose
+    );
+}
+
+#[test]
+fn test_max_turns_solver() {
+    let board = "XXXXXXXX\n\
+                 XM T  GX\n\
+                 XXXXXXXX\n";
+    let mut game = theseus::Game::from_board(board)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+    game.set_max_turns(Some(3));
+    assert_eq!(game.solve().map(|path| path.len()), Some(3));
+    game.set_max_turns(Some(2));
+    assert_eq!(game.solve(), None);
+    assert!(!game.is_winnable());
+}
+
+#[cfg(feature = "std-io")]
+#[test]
+fn test_from_file() {
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/board0.txt");
+    let game = theseus::Game::from_file(path)
+        .unwrap_or_else(|e| panic!("Failed to load board {}: {}", path, e));
+    let board = std::fs::read_to_string(path).expect("Failed to read fixture");
+    check_board_matches_game(&board, &game);
+
+    let missing = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/missing.txt");
+    assert!(matches!(
+        theseus::Game::from_file(missing),
+        Err(theseus::BoardError::Io(std::io::ErrorKind::NotFound))
+    ));
+}
+
+#[test]
+fn test_load_board_line_endings() {
+    let expected = "XXXX\n\
+                    XMTX\n\
+                    X GX\n\
+                    XXXX";
+    for board in [
+        "XXXX\r\nXMTX\r\nX GX\r\nXXXX\r\n",
+        "XXXX\r\nXMTX\r\nX GX\r\nXXXX\r",
+        "XXXX\nXMTX\nX GX\nXXXX\n",
+        "XXXX\nXMTX\nX GX\nXXXX\n\n",
+        "XXXX\nXMTX\nX GX\nXXXX",
+    ] {
+        let game = theseus::Game::from_board(board)
+            .unwrap_or_else(|e| panic!("Failed to create game from board {:?}: {}", board, e));
+        assert_eq!(game.height(), 4);
+        assert_eq!(game.width(), 4);
+        check_board_matches_game(expected, &game);
+    }
+}
+
+#[test]
+fn test_invalid_character_position() {
+    let board = "XXXXXX\n\
+                 XMT GX\n\
+                 X  q X\n\
+                 XXXXXX\n";
+    let err = theseus::Game::from_board(board).err();
+    assert_eq!(
+        err,
+        Some(theseus::BoardError::InvalidCharacter {
+            ch: 'q',
+            row: 2,
+            col: 3
+        })
+    );
+    assert_eq!(
+        err.unwrap().to_string(),
+        "Invalid character 'q' at row 2, col 3"
+    );
+}
+
+#[test]
+fn test_position_offset() {
+    use theseus::{Command, Position};
+    let pos = Position::new(2, 3);
+    assert_eq!(pos.offset(0, 0), Some(pos));
+    assert_eq!(pos.offset(-2, 1), Some(Position::new(0, 4)));
+    assert_eq!(pos.offset(5, -3), Some(Position::new(7, 0)));
+    assert_eq!(pos.offset(-3, 0), None);
+    assert_eq!(pos.offset(0, -4), None);
+    assert_eq!(Position::new(0, 0).step(Command::Up), None);
+    assert_eq!(Position::new(0, 0).step(Command::Left), None);
+    assert_eq!(pos.step(Command::Down), Some(Position::new(3, 3)));
+    assert_eq!(pos.step(Command::Skip), Some(pos));
+    assert_eq!(Position::from((4, 1)), Position::new(4, 1));
+    assert_eq!(<(usize, usize)>::from(pos), (2, 3));
+}
+
+#[test]
+fn test_render_styles() {
+    use theseus::RenderStyle;
+    let board = "XXXXX\n\
+                 XMT X\n\
+                 X  GX\n\
+                 XXXXX\n";
+    let game = theseus::Game::from_board(board)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+    assert_eq!(
+        game.render_with(RenderStyle::Ascii),
+        "#####\n#MT.#\n#..G#\n#####\n"
+    );
+    assert_eq!(
+        game.render_with(RenderStyle::Unicode),
+        "█████\n█MT █\n█  G█\n█████\n"
+    );
+    assert_eq!(game.render(), game.render_with(RenderStyle::default()));
+    assert!(game.render_with(RenderStyle::Ascii).is_ascii());
+}
+
+#[test]
+fn test_render_with_glyphs() {
+    let board = "XXXXX\n\
+                 XMT X\n\
+                 X  GX\n\
+                 XXXXX\n";
+    let mut game = theseus::Game::from_board(board)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+    let glyphs = theseus::Glyphs {
+        wall: '🧱',
+        empty: '🟩',
+        goal: '🏁',
+        theseus: '🦸',
+        minotaur: '🐂',
+        key: '🔑',
+        door: '🚪',
+        portal: '🌀',
+        ice: '🧊',
+        trap: '🕳',
+    };
+    assert_eq!(
+        game.render_with_glyphs(&glyphs),
+        "🧱🧱🧱🧱🧱\n🧱🐂🦸🟩🧱\n🧱🟩🟩🏁🧱\n🧱🧱🧱🧱🧱\n"
+    );
+
+    // Theseus is drawn over the goal he's standing on
+    game.theseus_move(theseus::Command::Down);
+    game.theseus_move(theseus::Command::Right);
+    let rendered = game.render_with_glyphs(&glyphs);
+    assert_eq!(rendered.lines().nth(2), Some("🧱🟩🟩🦸🧱"));
+    assert!(!rendered.contains('🏁'));
+
+    assert_eq!(
+        game.render_with_glyphs(&theseus::Glyphs::default()),
+        game.render()
+    );
+}
+
+#[test]
+fn test_render_ansi() {
+    let board = "XXXXX\n\
+                 XMT X\n\
+                 X  GX\n\
+                 XXXXX\n";
+    let game = theseus::Game::from_board(board)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+    let rendered = game.render_ansi();
+    let lines: Vec<&str> = rendered.lines().collect();
+    assert_eq!(lines.len(), 4);
+    assert_eq!(
+        lines[1],
+        "\x1b[90m\u{2588}\x1b[0m\x1b[31mM\x1b[0m\x1b[36mT\x1b[0m \x1b[90m\u{2588}\x1b[0m"
+    );
+    assert!(lines[2].contains("\x1b[32mG\x1b[0m"));
+
+    // Every line finishes with the colors reset
+    for line in &lines {
+        assert!(line.ends_with(theseus::ANSI_RESET));
+    }
+
+    // Stripping the escapes leaves the plain rendering
+    let plain = rendered
+        .replace(theseus::ANSI_RESET, "")
+        .replace("\x1b[90m", "")
+        .replace("\x1b[31m", "")
+        .replace("\x1b[36m", "")
+        .replace("\x1b[32m", "");
+    assert_eq!(plain, game.render());
+}
+
+#[test]
+fn test_display_game() {
+    let board = "XXXXX\n\
+                 XMT X\n\
+                 X  GX\n\
+                 XXXXX\n";
+    let game = theseus::Game::from_board(board)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+    assert_eq!(
+        format!("{}", game),
+        "\u{2588}\u{2588}\u{2588}\u{2588}\u{2588}\n\
+         \u{2588}MT \u{2588}\n\
+         \u{2588}  G\u{2588}\n\
+         \u{2588}\u{2588}\u{2588}\u{2588}\u{2588}\n"
+    );
+    assert_eq!(game.to_string(), game.render());
+}
+
+#[test]
+fn test_display_command_and_status() {
+    use theseus::{Command, GameStatus};
+    let names = [
+        (Command::Up, "Up"),
+        (Command::Down, "Down"),
+        (Command::Left, "Left"),
+        (Command::Right, "Right"),
+        (Command::Skip, "Skip"),
+    ];
+    for (command, name) in names {
+        assert_eq!(command.to_string(), name);
+        assert_eq!(name.parse::<Command>(), Ok(command));
+    }
+
+    assert_eq!(GameStatus::Win.to_string(), "Win");
+    assert_eq!(GameStatus::Lose.to_string(), "Lose");
+    assert_eq!(GameStatus::Continue.to_string(), "Continue");
+}
+
+#[test]
+fn test_game_eq_and_hash() {
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+    fn hash_of(game: &theseus::Game) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        game.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    let board = "XXXXX\n\
+                 XMT X\n\
+                 X  GX\n\
+                 XXXXX\n";
+    let game = theseus::Game::from_board(board)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+    let mut copy = game.clone();
+    assert!(copy == game);
+    assert_eq!(hash_of(&copy), hash_of(&game));
+
+    copy.theseus_move(theseus::Command::Right);
+    assert!(copy != game);
+    assert_ne!(hash_of(&copy), hash_of(&game));
+
+    // Moving back restores equality
+    copy.theseus_move(theseus::Command::Left);
+    assert!(copy == game);
+    assert_eq!(hash_of(&copy), hash_of(&game));
+}
+
+#[test]
+fn test_replay() {
+    use theseus::{Command, GameStatus};
+    let board = "XXXXX\n\
+                 XMT X\n\
+                 X  GX\n\
+                 XXXXX\n";
+    let new_game = || {
+        theseus::Game::from_board(board)
+            .unwrap_or_else(|_| panic!("Failed to create game from board {}", board))
+    };
+
+    // Winning on the second command leaves the rest unplayed
+    let mut game = new_game();
+    let commands = [Command::Down, Command::Right, Command::Up];
+    assert_eq!(game.replay(&commands), (GameStatus::Win, 2));
+    assert_eq!(game.theseus_position(), game.goal_position());
+    assert_eq!(game.turns(), 2);
+
+    // Waiting lets the Minotaur catch Theseus straight away
+    let mut game = new_game();
+    let commands = [Command::Skip, Command::Down, Command::Right];
+    assert_eq!(game.replay(&commands), (GameStatus::Lose, 1));
+
+    // Running out of commands mid-game
+    let mut game = new_game();
+    assert_eq!(game.replay(&[Command::Down]), (GameStatus::Continue, 1));
+    assert_eq!(game.replay(&[]), (GameStatus::Continue, 0));
+
+    // A finished game plays nothing
+    assert_eq!(game.replay(&[Command::Right]), (GameStatus::Win, 1));
+    assert_eq!(game.replay(&[Command::Left]), (GameStatus::Win, 0));
+}
+
+#[test]
+fn test_parse_commands() {
+    use theseus::Command;
+    assert_eq!(
+        theseus::parse_commands("d d s w skip"),
+        Ok(vec![
+            Command::Right,
+            Command::Right,
+            Command::Down,
+            Command::Up,
+            Command::Skip,
+        ])
+    );
+    assert_eq!(
+        theseus::parse_commands("  Up\tleft\n. wait  "),
+        Ok(vec![
+            Command::Up,
+            Command::Left,
+            Command::Skip,
+            Command::Skip
+        ])
+    );
+    assert_eq!(theseus::parse_commands(""), Ok(vec![]));
+
+    let err = theseus::parse_commands("w a jump s x").unwrap_err();
+    assert_eq!(err, "Unknown command: jump (token 2)");
+}
+
+#[cfg(feature = "std-io")]
+#[test]
+fn test_run_game() {
+    use theseus::GameStatus;
+    let board = "XXXXX\n\
+                 XMT X\n\
+                 X  GX\n\
+                 XXXXX\n";
+    let new_game = || {
+        theseus::Game::from_board(board)
+            .unwrap_or_else(|_| panic!("Failed to create game from board {}", board))
+    };
+
+    let mut game = new_game();
+    let mut output = Vec::new();
+    let status = theseus::run_game(&mut game, "s\njump\nd\nw\n".as_bytes(), &mut output);
+    assert_eq!(status, GameStatus::Win);
+    assert_eq!(game.turns(), 2);
+    let output = String::from_utf8(output).unwrap();
+    assert!(output.contains("Unknown command: jump"));
+    assert!(output.ends_with("You win!\n"));
+
+    // Quitting and running out of input both leave the game in progress
+    let mut game = new_game();
+    let mut output = Vec::new();
+    let status = theseus::run_game(&mut game, "s\nq\nd\n".as_bytes(), &mut output);
+    assert_eq!(status, GameStatus::Continue);
+    assert_eq!(game.turns(), 1);
+
+    let mut game = new_game();
+    let status = theseus::run_game(&mut game, "".as_bytes(), &mut Vec::new());
+    assert_eq!(status, GameStatus::Continue);
+    assert_eq!(game.turns(), 0);
+
+    let mut game = new_game();
+    let status = theseus::run_game(&mut game, ".\n".as_bytes(), &mut Vec::new());
+    assert_eq!(status, GameStatus::Lose);
+}
+
+#[test]
+fn test_grid_set() {
+    use theseus::{BoardError, Cell, Grid};
+    let mut grid = Grid::new(2, 2, vec![Cell::Empty; 4]);
+    assert_eq!(grid.set(0, 1, 'X'), Ok(()));
+    assert!(grid.is_wall(0, 1));
+    assert_eq!(grid.set(0, 1, ' '), Ok(()));
+    assert!(grid.is_empty(0, 1));
+    assert_eq!(grid.set(1, 0, 'G'), Ok(()));
+    assert!(grid.is_goal(1, 0));
+    // Every other tile goes too
+    assert_eq!(grid.set(0, 0, '3'), Ok(()));
+    assert_eq!(grid.get(0, 0), Some(Cell::Mud(3)));
+    assert_eq!(grid.set(0, 0, 'K'), Ok(()));
+    assert_eq!(grid.get(0, 0), Some(Cell::Key));
+
+    assert_eq!(
+        grid.set(2, 0, 'X'),
+        Err(BoardError::OutOfBounds { row: 2, col: 0 })
+    );
+    assert_eq!(
+        grid.set(1, 1, 'T'),
+        Err(BoardError::InvalidCharacter {
+            ch: 'T',
+            row: 1,
+            col: 1
+        })
+    );
+    assert!(grid.is_empty(1, 1));
+}
+
+#[test]
+fn test_game_set_cell() {
+    use theseus::BoardError;
+    let board = "XXXXX\n\
+                 XMT X\n\
+                 X  GX\n\
+                 XXXXX\n";
+    let mut game = theseus::Game::from_board(board)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+    assert_eq!(game.set_cell(1, 3, 'X'), Ok(()));
+    assert!(game.is_wall(1, 3));
+    assert_eq!(game.set_cell(0, 0, ' '), Ok(()));
+    assert!(!game.is_wall(0, 0));
+
+    // Entities can't be walled in
+    assert_eq!(
+        game.set_cell(1, 2, 'X'),
+        Err(BoardError::Occupied { row: 1, col: 2 })
+    );
+    assert_eq!(
+        game.set_cell(1, 1, 'X'),
+        Err(BoardError::Occupied { row: 1, col: 1 })
+    );
+    assert!(game.is_theseus(1, 2) && !game.is_wall(1, 2));
+
+    // There's always exactly one goal
+    assert_eq!(game.set_cell(2, 3, 'X'), Err(BoardError::NoGoal));
+    assert_eq!(game.set_cell(2, 1, 'G'), Err(BoardError::MultipleGoal));
+    assert!(game.is_goal(2, 3));
+
+    // One key and one door at most; rewriting the same cell is fine
+    assert_eq!(game.set_cell(2, 1, 'K'), Ok(()));
+    assert_eq!(game.set_cell(2, 1, 'K'), Ok(()));
+    assert_eq!(game.set_cell(2, 2, 'K'), Err(BoardError::MultipleKey));
+    assert_eq!(game.set_cell(0, 1, 'D'), Ok(()));
+    assert_eq!(game.set_cell(0, 2, 'D'), Err(BoardError::MultipleDoor));
+    assert_eq!(theseus::Game::validate(&game.to_board()), vec![]);
+
+    assert_eq!(
+        game.set_cell(9, 9, ' '),
+        Err(BoardError::OutOfBounds { row: 9, col: 9 })
+    );
+}
+
+#[test]
+fn test_game_builder() {
+    use theseus::{BoardError, GameBuilder};
+    let board = "XXXXX\n\
+                 XMT X\n\
+                 X  GX\n\
+                 XXXXX\n";
+    let expected = theseus::Game::from_board(board)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+
+    let mut builder = GameBuilder::new(5, 4);
+    for col in 0..5 {
+        builder = builder.set_wall(0, col).set_wall(3, col);
+    }
+    for row in 1..3 {
+        builder = builder.set_wall(row, 0).set_wall(row, 4);
+    }
+    let game = builder
+        .theseus(1, 2)
+        .minotaur(1, 1)
+        .goal(2, 3)
+        .build()
+        .unwrap();
+    assert!(game == expected);
+    assert_eq!(game.to_board(), board);
+
+    // The same invariants as parsing a board
+    let base = GameBuilder::new(3, 3).theseus(0, 0).minotaur(1, 1);
+    assert_eq!(base.clone().build().err(), Some(BoardError::NoGoal));
+    assert_eq!(
+        base.clone().goal(2, 2).theseus(2, 0).build().err(),
+        Some(BoardError::MultipleTheseus)
+    );
+    assert_eq!(
+        base.clone().goal(2, 2).goal(2, 1).build().err(),
+        Some(BoardError::MultipleGoal)
+    );
+    assert_eq!(
+        GameBuilder::new(3, 3)
+            .theseus(0, 0)
+            .goal(2, 2)
+            .build()
+            .err(),
+        Some(BoardError::NoMinotaur)
+    );
+    assert_eq!(
+        base.clone().goal(3, 0).build().err(),
+        Some(BoardError::OutOfBounds { row: 3, col: 0 })
+    );
+    assert_eq!(
+        base.clone().goal(2, 2).set_wall(1, 1).build().err(),
+        Some(BoardError::Occupied { row: 1, col: 1 })
+    );
+    assert_eq!(
+        GameBuilder::new(0, 0).build().err(),
+        Some(BoardError::EmptyBoard)
+    );
+
+    // Several Minotaurs are fine
+    let game = base.goal(2, 2).minotaur(0, 2).build().unwrap();
+    assert_eq!(game.minotaur_positions().len(), 2);
+}
+
+#[test]
+fn test_from_board_with() {
+    use theseus::{BoardError, Glyphs, RenderStyle};
+    let glyphs = Glyphs {
+        wall: '#',
+        empty: '.',
+        goal: '*',
+        theseus: '@',
+        minotaur: '&',
+        ..Glyphs::BOARD
+    };
+    let board = "#####\n\
+                 #&@.#\n\
+                 #..*#\n\
+                 #####\n";
+    let game = theseus::Game::from_board_with(board, &glyphs)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+    let expected = theseus::Game::from_board(
+        "XXXXX\n\
+         XMT X\n\
+         X  GX\n\
+         XXXXX\n",
+    )
+    .unwrap();
+    assert!(game == expected);
+    assert!(game.is_wall(0, 0));
+    assert!(game.is_theseus(1, 2));
+    assert!(game.is_minotaur(1, 1));
+    assert!(game.is_goal(2, 3));
+    assert_eq!(game.render_with_glyphs(&glyphs), board);
+
+    // The default board characters are no longer accepted
+    assert_eq!(
+        theseus::Game::from_board_with("#X@&*\n", &glyphs).err(),
+        Some(BoardError::InvalidCharacter {
+            ch: 'X',
+            row: 0,
+            col: 1
+        })
+    );
+
+    // Rendered boards parse back with their style's glyphs
+    let ascii = game.render_with(RenderStyle::Ascii);
+    let reparsed = theseus::Game::from_board_with(&ascii, &RenderStyle::Ascii.glyphs()).unwrap();
+    assert!(reparsed == game);
+}
+
+#[test]
+fn test_key_opens_door() {
+    use theseus::{BoardError, Command, GameStatus};
+    let board = "XXXXXXX\n\
+                 XT  KXX\n\
+                 XXDXXMX\n\
+                 X G XXX\n\
+                 XXXXXXX\n";
+    let mut game = theseus::Game::from_board(board)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+    assert!(game.is_key(1, 4));
+    assert!(game.is_door(2, 2));
+    assert!(!game.is_empty(2, 2));
+    assert!(!game.has_key());
+    assert_eq!(game.to_board(), board);
+
+    // The door is locked until the key is picked up
+    game.step(Command::Right);
+    game.step(Command::Down);
+    assert_eq!(game.theseus_position(), theseus::Position::new(1, 2));
+
+    let solution = game.solve().unwrap();
+    assert_eq!(solution.len(), 6);
+    assert_eq!(solution[0], Command::Right);
+
+    game.step(Command::Right);
+    game.step(Command::Right);
+    assert!(game.has_key());
+    assert!(!game.is_key(1, 4));
+    assert!(!game.is_door(2, 2));
+    assert!(game.is_empty(2, 2));
+    assert_eq!(game.to_board().lines().nth(2), Some("XX XXMX"));
+
+    // Undoing the pickup locks the door again
+    assert!(game.undo());
+    assert!(!game.has_key());
+    assert!(game.is_door(2, 2));
+    assert!(game.redo());
+
+    let commands = [Command::Left, Command::Left, Command::Down, Command::Down];
+    assert_eq!(game.replay(&commands), (GameStatus::Win, 4));
+
+    assert_eq!(
+        theseus::Game::from_board("TMGKK\n").err(),
+        Some(BoardError::MultipleKey)
+    );
+    assert_eq!(
+        theseus::Game::from_board("TMGDD\n").err(),
+        Some(BoardError::MultipleDoor)
+    );
+}
+
+#[test]
+fn test_minotaur_blocked_by_locked_door() {
+    use theseus::{Command, GameStatus, Position};
+    let board = "XXXXX\n\
+                 XT KX\n\
+                 XDXGX\n\
+                 XMXXX\n\
+                 XXXXX\n";
+    let mut game = theseus::Game::from_board(board)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+    assert_eq!(game.step(Command::Right), GameStatus::Continue);
+    assert_eq!(game.minotaur_position(), Position::new(3, 1));
+
+    // Once the key is taken the Minotaur can come through too
+    assert_eq!(game.step(Command::Right), GameStatus::Continue);
+    assert_eq!(game.minotaur_position(), Position::new(2, 1));
+    assert_eq!(game.step(Command::Down), GameStatus::Win);
+}
+
+#[test]
+fn test_portals() {
+    use theseus::{Command, GameStatus, Position};
+    let board = "XXXXXX\n\
+                 XTP MX\n\
+                 XXXXXX\n\
+                 XGP  X\n\
+                 XXXXXX\n";
+    let new_game = || {
+        theseus::Game::from_board(board)
+            .unwrap_or_else(|_| panic!("Failed to create game from board {}", board))
+    };
+    let mut game = new_game();
+    assert!(game.is_portal(1, 2) && game.is_portal(3, 2));
+    assert!(!game.is_empty(1, 2));
+    assert_eq!(game.to_board(), board);
+
+    assert_eq!(game.step(Command::Right), GameStatus::Continue);
+    assert_eq!(game.theseus_position(), Position::new(3, 2));
+    assert_eq!(game.minotaur_position(), Position::new(1, 3));
+
+    // Waiting on a portal doesn't use it
+    game.theseus_move(Command::Skip);
+    assert_eq!(game.theseus_position(), Position::new(3, 2));
+    assert!(game.undo());
+
+    // Walking back onto a portal sends Theseus back again
+    game.step(Command::Right);
+    game.step(Command::Left);
+    assert_eq!(game.theseus_position(), Position::new(1, 2));
+    assert!(game.undo());
+    assert_eq!(game.theseus_position(), Position::new(3, 3));
+
+    let game = new_game();
+    assert_eq!(game.solve(), Some(vec![Command::Right, Command::Left]));
+
+    // Minotaurs walk over portals without using them, and one standing on
+    // a portal catches Theseus before he can go through
+    let mut game = new_game();
+    game.minotaur_move();
+    game.minotaur_move();
+    assert_eq!(game.minotaur_position(), Position::new(1, 2));
+    game.theseus_move(Command::Right);
+    assert_eq!(game.theseus_position(), Position::new(1, 2));
+    assert_eq!(game.status(), GameStatus::Lose);
+}
+
+#[test]
+fn test_portals_come_in_pairs() {
+    use theseus::BoardError;
+    assert_eq!(
+        theseus::Game::from_board("TMGP\n").err(),
+        Some(BoardError::UnpairedPortal)
+    );
+    assert_eq!(
+        theseus::Game::from_board("TMGPPP\n").err(),
+        Some(BoardError::UnpairedPortal)
+    );
+    assert!(theseus::Game::from_board("TMGPP\n").is_ok());
+
+    let mut game = theseus::Game::from_board("TMG P P\n").unwrap();
+    assert_eq!(game.set_cell(0, 3, 'P'), Err(BoardError::UnpairedPortal));
+    assert_eq!(game.set_cell(0, 4, ' '), Err(BoardError::UnpairedPortal));
+}
+
+#[test]
+fn test_ice_slide_stops_at_wall() {
+    use theseus::{Command, Position};
+    let board = "XXXXXXX\n\
+                 XTIIIXX\n\
+                 XXXXXXX\n\
+                 XG   MX\n\
+                 XXXXXXX\n";
+    let mut game = theseus::Game::from_board(board)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+    assert!(game.is_ice(1, 2));
+    assert!(!game.is_empty(1, 2));
+    assert_eq!(game.to_board(), board);
+
+    game.step(Command::Right);
+    assert_eq!(game.theseus_position(), Position::new(1, 4));
+    // The Minotaur walks over ice normally
+    assert_eq!(game.minotaur_position(), Position::new(3, 4));
+
+    // Sliding back stops on the first tile that isn't ice
+    game.step(Command::Left);
+    assert_eq!(game.theseus_position(), Position::new(1, 1));
+}
+
+#[test]
+fn test_ice_slide_stops_at_edge() {
+    use theseus::{Command, GameStatus, Position};
+    let board = "TIII\n\
+                 XXXX\n\
+                 MX G\n";
+    let mut game = theseus::Game::from_board(board)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+    game.step(Command::Right);
+    assert_eq!(game.theseus_position(), Position::new(0, 3));
+
+    // A Minotaur on the ice stops the slide and catches Theseus
+    let mut game = theseus::Game::from_board("TIIMI\nXXXXX\nG    \n").unwrap();
+    game.set_cell(0, 3, 'I').unwrap();
+    game.theseus_move(Command::Right);
+    assert_eq!(game.theseus_position(), Position::new(0, 3));
+    assert_eq!(game.status(), GameStatus::Lose);
+}
+
+/// Shuttles the goal back and forth between two columns
+struct ShuttleGoal;
+
+impl theseus::GoalPolicy for ShuttleGoal {
+    fn choose(&self, _game: &theseus::Game, goal: theseus::Position) -> theseus::Command {
+        if goal.col == 5 {
+            theseus::Command::Left
+        } else {
+            theseus::Command::Right
+        }
+    }
+}
+
+#[test]
+fn test_moving_goal() {
+    use theseus::{Command, GameStatus, Position};
+    let board = "XXXXXXX\n\
+                 XT   GX\n\
+                 XXXXXXX\n\
+                 XMXXXXX\n\
+                 XXXXXXX\n";
+    let new_game = || {
+        theseus::Game::from_board(board)
+            .unwrap_or_else(|_| panic!("Failed to create game from board {}", board))
+    };
+
+    // The goal stays put by default
+    let mut game = new_game();
+    game.step(Command::Skip);
+    assert_eq!(game.goal_position(), Position::new(1, 5));
+    assert!(game.move_goal(Command::Left));
+    assert!(game.is_goal(1, 4) && !game.is_goal(1, 5));
+    assert!(!game.move_goal(Command::Up));
+    assert_eq!(game.goal_position(), Position::new(1, 4));
+
+    let mut game = new_game();
+    game.set_goal_policy(ShuttleGoal);
+    assert_eq!(game.step(Command::Right), GameStatus::Continue);
+    assert_eq!(game.goal_position(), Position::new(1, 4));
+    assert_eq!(game.step(Command::Right), GameStatus::Continue);
+    assert_eq!(game.goal_position(), Position::new(1, 5));
+
+    // Theseus waits where the goal is heading rather than where it is
+    assert_eq!(game.step(Command::Right), GameStatus::Win);
+    assert_eq!(game.theseus_position(), Position::new(1, 4));
+    assert_eq!(game.goal_position(), Position::new(1, 4));
+
+    // Undo puts the goal back too
+    assert!(game.undo());
+    assert_eq!(game.goal_position(), Position::new(1, 5));
+    assert!(game.is_goal(1, 5) && !game.is_goal(1, 4));
+
+    let mut game = new_game();
+    game.set_goal_policy(ShuttleGoal);
+    assert_eq!(game.solve().map(|path| path.len()), Some(3));
+}
+
+#[test]
+fn test_render_fog() {
+    let board = "XXXXXXX\n\
+                 XT    X\n\
+                 X XXX X\n\
+                 X    MX\n\
+                 XXXXXGX\n";
+    let game = theseus::Game::from_board(board)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+    let w = theseus::WALL_GLYPH;
+    assert_eq!(
+        game.render_fog(1),
+        format!("{w}{w}{w}????\n{w}T ????\n{w} {w}????\n???????\n???????\n")
+    );
+
+    // Theseus is always visible, and the Minotaur only when close enough
+    assert_eq!(
+        game.render_fog(0),
+        "???????\n?T?????\n???????\n???????\n???????\n"
+    );
+    assert!(!game.render_fog(3).contains('M'));
+    assert!(game.render_fog(4).contains('M'));
+    assert_eq!(game.render_fog(6), game.render());
+
+    assert_eq!(
+        theseus::chebyshev(theseus::Position::new(1, 1), theseus::Position::new(3, 5)),
+        4
+    );
+}
+
+#[test]
+fn test_trap() {
+    use theseus::{Command, GameStatus, Position};
+    let board = "XXXXXXX\n\
+                 XT^GXMX\n\
+                 X   XXX\n\
+                 XXXXXXX\n";
+    let mut game = theseus::Game::from_board(board)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+    assert!(game.is_trap(1, 2));
+    assert!(!game.is_empty(1, 2) && !game.is_wall(1, 2));
+    assert_eq!(game.to_board(), board);
+
+    // The shortcut is a trap, so the way to the goal goes around it
+    assert_eq!(
+        game.solve(),
+        Some(vec![
+            Command::Down,
+            Command::Right,
+            Command::Right,
+            Command::Up
+        ])
+    );
+    assert_eq!(game.step(Command::Right), GameStatus::Lose);
+    assert_eq!(game.theseus_position(), Position::new(1, 2));
+
+    // The Minotaur walks over traps unharmed
+    let board = "XXXXXX\n\
+                 XT ^MX\n\
+                 XXXXGX\n\
+                 XXXXXX\n";
+    let mut game = theseus::Game::from_board(board)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+    assert_eq!(game.step(Command::Skip), GameStatus::Continue);
+    assert_eq!(game.minotaur_position(), Position::new(1, 3));
+    assert_eq!(game.step(Command::Skip), GameStatus::Continue);
+    assert_eq!(game.minotaur_position(), Position::new(1, 2));
+}
+
+#[test]
+fn test_solve_min_cost() {
+    use theseus::{Cell, Command};
+    let board = "XXXXXXX\n\
+                 XT999GX\n\
+                 X     X\n\
+                 XXXXXXX\n\
+                 XMXXXXX\n\
+                 XXXXXXX\n";
+    let game = theseus::Game::from_board(board)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+    assert!(game.is_mud(1, 2));
+    assert!(!game.is_empty(1, 2) && !game.is_wall(1, 2));
+    assert_eq!(game.to_board(), board);
+    assert_eq!(Cell::Mud(9).cost(), 9);
+    assert_eq!(Cell::Empty.cost(), 1);
+
+    // The shortest way is straight through the mud...
+    let path = game.solve().unwrap();
+    assert_eq!(path, vec![Command::Right; 4]);
+
+    // ...while the cheapest goes around it
+    let (path, cost) = game.solve_min_cost().unwrap();
+    assert_eq!(
+        path,
+        vec![
+            Command::Down,
+            Command::Right,
+            Command::Right,
+            Command::Right,
+            Command::Right,
+            Command::Up,
+        ]
+    );
+    assert_eq!(cost, 6);
+
+    // Light mud is worth walking through
+    let game = theseus::Game::from_board(&board.replace('9', "1")).unwrap();
+    assert_eq!(game.solve_min_cost(), Some((vec![Command::Right; 4], 4)));
+
+    // A turn limit can rule out the way round, leaving only the mud, or
+    // leave no way at all
+    let mut game = theseus::Game::from_board(board).unwrap();
+    game.set_max_turns(Some(5));
+    assert_eq!(game.solve_min_cost().map(|(_, cost)| cost), Some(28));
+    game.set_max_turns(Some(3));
+    assert_eq!(game.solve_min_cost(), None);
+
+    // A won game costs nothing more, and a lost one can't be won
+    let mut game = theseus::Game::from_board(board).unwrap();
+    for _ in 0..4 {
+        game.step(Command::Right);
+    }
+    assert_eq!(game.status(), theseus::GameStatus::Win);
+    assert_eq!(game.solve_min_cost(), Some((Vec::new(), 0)));
+    let mut game = theseus::Game::from_board(board).unwrap();
+    game.set_max_turns(Some(1));
+    game.step(Command::Skip);
+    assert_eq!(game.status(), theseus::GameStatus::Lose);
+    assert_eq!(game.solve_min_cost(), None);
+}
+
+#[test]
+fn test_game_config() {
+    use theseus::{BoardError, Command, Game, GameConfig, GameStatus, Position};
+    let board = "XXXXXXX\n\
+                 XM   TX\n\
+                 X    GX\n\
+                 XXXXXXX\n";
+    let default = Game::from_board(board)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+    assert_eq!(default.minotaur_speed(), 1);
+    assert_eq!(default.max_turns(), None);
+    assert_eq!(GameConfig::default().minotaur_speed, 1);
+
+    let config = GameConfig {
+        minotaur_speed: 2,
+        max_turns: Some(10),
+        ..GameConfig::default()
+    };
+    let mut fast = Game::from_board_with_config(board, config).unwrap();
+    assert_eq!(fast.minotaur_speed(), 2);
+    assert_eq!(fast.max_turns(), Some(10));
+
+    let mut slow = default.clone();
+    slow.step(Command::Skip);
+    fast.step(Command::Skip);
+    assert_eq!(slow.minotaur_position(), Position::new(1, 2));
+    assert_eq!(fast.minotaur_position(), Position::new(1, 3));
+    assert_eq!(fast.step(Command::Skip), GameStatus::Lose);
+
+    let config = GameConfig {
+        allow_multiple_minotaurs: false,
+        ..GameConfig::default()
+    };
+    let two_minotaurs = "TMMG\n";
+    assert!(Game::from_board(two_minotaurs).is_ok());
+    assert_eq!(
+        Game::from_board_with_config(two_minotaurs, config).err(),
+        Some(BoardError::MultipleMinotaur)
+    );
+}
+
+#[test]
+fn test_minotaur_axis_priority() {
+    use theseus::{Axis, Command, Game, GameConfig, Position};
+    let board = "XXXXXX\n\
+                 XM   X\n\
+                 X    X\n\
+                 X   TX\n\
+                 XXXXGX\n";
+    let mut horizontal = Game::from_board(board)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+    assert_eq!(horizontal.minotaur_axis_priority(), Axis::HorizontalFirst);
+    let config = GameConfig {
+        minotaur_axis_priority: Axis::VerticalFirst,
+        ..GameConfig::default()
+    };
+    let mut vertical = Game::from_board_with_config(board, config).unwrap();
+
+    horizontal.step(Command::Skip);
+    vertical.step(Command::Skip);
+    assert_eq!(horizontal.minotaur_position(), Position::new(1, 2));
+    assert_eq!(vertical.minotaur_position(), Position::new(2, 1));
+
+    // A blocked preferred axis still falls back to the other one
+    let mut game = Game::from_board("XXXX\nXM X\nXXTX\nXXGX\n").unwrap();
+    game.set_minotaur_axis_priority(Axis::VerticalFirst);
+    game.step(Command::Skip);
+    assert_eq!(game.minotaur_position(), Position::new(1, 2));
+}
+
+#[test]
+fn test_theseus_move_reports_blocked() {
+    use theseus::{Command, Position};
+    let board = "XXXXX\n\
+                 XMT X\n\
+                 X  GX\n\
+                 XXXXX\n";
+    let mut game = theseus::Game::from_board(board)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+    assert!(!game.theseus_move(Command::Up));
+    assert_eq!(game.theseus_position(), Position::new(1, 2));
+    assert_eq!(game.turns(), 1);
+
+    assert!(game.theseus_move(Command::Skip));
+    assert_eq!(game.theseus_position(), Position::new(1, 2));
+
+    assert!(game.theseus_move(Command::Right));
+    assert_eq!(game.theseus_position(), Position::new(1, 3));
+    assert!(!game.theseus_move(Command::Right));
+    assert_eq!(game.theseus_position(), Position::new(1, 3));
+
+    // Off the edge of the board is blocked too
+    let mut game = theseus::Game::from_board("TMG\n").unwrap();
+    assert!(!game.theseus_move(Command::Left));
+    assert!(!game.theseus_move(Command::Up));
+    assert_eq!(game.theseus_position(), Position::new(0, 0));
+}
+
+#[test]
+fn test_visited() {
+    use theseus::Command;
+    fn sorted(game: &theseus::Game) -> Vec<(usize, usize)> {
+        let mut cells: Vec<(usize, usize)> = game.visited().collect();
+        cells.sort();
+        cells
+    }
+    let board = "XXXXXX\n\
+                 XT   X\n\
+                 X    X\n\
+                 XXXXGX\n\
+                 XMXXXX\n\
+                 XXXXXX\n";
+    let mut game = theseus::Game::from_board(board)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+    assert_eq!(sorted(&game), vec![(1, 1)]);
+
+    game.step(Command::Right);
+    game.step(Command::Down);
+    game.step(Command::Up);
+    game.theseus_move(Command::Up);
+    assert_eq!(sorted(&game), vec![(1, 1), (1, 2), (2, 2)]);
+
+    // Undo only drops cells first stepped on in the turns it rewinds
+    assert!(game.undo());
+    assert!(game.undo());
+    assert_eq!(sorted(&game), vec![(1, 1), (1, 2), (2, 2)]);
+    assert!(game.undo());
+    assert_eq!(sorted(&game), vec![(1, 1), (1, 2)]);
+    assert!(game.redo());
+    assert_eq!(sorted(&game), vec![(1, 1), (1, 2), (2, 2)]);
+    assert!(game.undo());
+    assert!(game.undo());
+    assert_eq!(sorted(&game), vec![(1, 1)]);
+}
+
+#[test]
+fn test_on_turn_callback() {
+    use std::sync::{Arc, Mutex};
+    use theseus::{Command, GameStatus, Position, TurnEvent};
+    let board = "XXXXX\n\
+                 XMT X\n\
+                 X  GX\n\
+                 XXXXX\n";
+    let mut game = theseus::Game::from_board(board)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let sink = Arc::clone(&events);
+    game.set_on_turn(move |event: &TurnEvent| sink.lock().unwrap().push(event.clone()));
+
+    // Only full turns are reported
+    game.theseus_move(Command::Skip);
+    assert!(game.undo());
+    assert!(events.lock().unwrap().is_empty());
+
+    game.replay(&[Command::Up, Command::Down, Command::Right]);
+    assert_eq!(
+        *events.lock().unwrap(),
+        vec![TurnEvent {
+            command: Command::Up,
+            theseus: Position::new(1, 2),
+            minotaurs: vec![Position::new(1, 2)],
+            status: GameStatus::Lose,
+        },]
+    );
+
+    let mut game = theseus::Game::from_board(board).unwrap();
+    let sink = Arc::clone(&events);
+    events.lock().unwrap().clear();
+    game.set_on_turn(move |event: &TurnEvent| sink.lock().unwrap().push(event.clone()));
+    game.replay(&[Command::Down, Command::Right]);
+    let events = events.lock().unwrap();
+    let summary: Vec<(Command, Position, GameStatus)> = events
+        .iter()
+        .map(|event| (event.command, event.theseus, event.status))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            (Command::Down, Position::new(2, 2), GameStatus::Continue),
+            (Command::Right, Position::new(2, 3), GameStatus::Win),
+        ]
+    );
+    assert_eq!(events[0].minotaurs, vec![Position::new(1, 2)]);
+}
+
+#[test]
+fn test_bit_grid_matches_grid() {
+    let board = "XXXXXXXXX\n\
+                 X  T  XXX\n\
+                 X XXX XXX\n\
+                 X  M  XXX\n\
+                 XXXXX XXX\n\
+                 XXXXX  GX\n\
+                 XXXXXXXXX\n";
+    let games = [
+        theseus::Game::from_board(board)
+            .unwrap_or_else(|_| panic!("Failed to create game from board {}", board)),
+        // Large enough for walls to span many words
+        theseus::Game::generate(41, 37, 5),
+    ];
+    for game in &games {
+        let grid = game.grid();
+        let bits = theseus::BitGrid::from(grid);
+        assert_eq!(bits.width(), grid.width());
+        assert_eq!(bits.height(), grid.height());
+        // One past the edge on each side, to check out-of-bounds too
+        for row in 0..=grid.height() {
+            for col in 0..=grid.width() {
+                assert_eq!(bits.is_wall(row, col), grid.is_wall(row, col));
+                assert_eq!(bits.is_goal(row, col), grid.is_goal(row, col));
+            }
+        }
+        // The solver searches through a BitGrid and still finds the way
+        assert!(game.solve().is_some());
+    }
+}
+
+#[test]
+fn test_reset() {
+    use theseus::{Command, GameStatus, Position};
+    let board = "XXXXXXXX\n\
+                 XT K  GX\n\
+                 XXXXXX X\n\
+                 XM     X\n\
+                 XXXXXXXX\n";
+    let fresh = theseus::Game::from_board(board)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+    let mut game = fresh.clone();
+    game.set_max_turns(Some(3));
+    game.set_goal_policy(StepLeftGoal);
+    assert_eq!(
+        game.replay(&[Command::Right, Command::Right, Command::Left]),
+        (GameStatus::Lose, 3)
+    );
+    assert!(game.has_key());
+    assert_eq!(game.goal_position(), Position::new(1, 4));
+
+    game.reset();
+    assert!(game == fresh);
+    assert_eq!(game.to_board(), board);
+    assert_eq!(game.status(), GameStatus::Continue);
+    assert_eq!(game.turns(), 0);
+    assert!(!game.undo());
+    assert_eq!(game.visited().collect::<Vec<_>>(), vec![(1, 1)]);
+    // Settings survive a reset
+    assert_eq!(game.max_turns(), Some(3));
+}
+
+/// Moves the goal one column left every turn
+struct StepLeftGoal;
+
+impl theseus::GoalPolicy for StepLeftGoal {
+    fn choose(&self, _game: &theseus::Game, _goal: theseus::Position) -> theseus::Command {
+        theseus::Command::Left
+    }
+}
+
+#[test]
+fn test_snapshot_restore() {
+    use theseus::{Command, GameStatus};
+    let board = "XXXXXXXX\n\
+                 XT K  GX\n\
+                 XXXXXX X\n\
+                 XM     X\n\
+                 XXXXXXXX\n";
+    let mut game = theseus::Game::from_board(board)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+    game.step(Command::Right);
+    let before = game.clone();
+    let snapshot = game.snapshot();
+
+    game.replay(&[Command::Right, Command::Right, Command::Skip]);
+    assert!(game.has_key());
+    assert!(game != before);
+
+    game.restore(&snapshot);
+    assert!(game == before);
+    assert_eq!(game.turns(), 1);
+    assert_eq!(game.snapshot(), snapshot);
+    assert!(!game.redo());
+    let mut visited: Vec<(usize, usize)> = game.visited().collect();
+    visited.sort();
+    assert_eq!(visited, vec![(1, 1), (1, 2)]);
+
+    // History from before the snapshot is untouched
+    assert!(game.undo());
+    assert_eq!(game.theseus_position(), theseus::Position::new(1, 1));
+    assert!(!game.undo());
+
+    // Lookahead from a lost position rolls back too
+    let snapshot = game.snapshot();
+    game.set_max_turns(Some(1));
+    assert_eq!(game.step(Command::Skip), GameStatus::Lose);
+    game.restore(&snapshot);
+    assert_eq!(game.status(), GameStatus::Continue);
+
+    // Undoing past the snapshot before restoring still leaves its cell visited
+    let mut game = theseus::Game::from_board(board).unwrap();
+    game.step(Command::Right);
+    game.step(Command::Right);
+    game.undo();
+    let snapshot = game.snapshot();
+    game.undo();
+    game.restore(&snapshot);
+    assert_eq!(game.theseus_position(), theseus::Position::new(1, 2));
+    let mut visited: Vec<(usize, usize)> = game.visited().collect();
+    visited.sort();
+    assert_eq!(visited, vec![(1, 1), (1, 2)]);
+}
+
+#[test]
+fn test_is_lost_cause() {
+    use theseus::{Command, GameStatus};
+    let board = "XXXXXXX\n\
+                 XT   GX\n\
+                 XXXXXXX\n\
+                 XMXXXXX\n\
+                 XXXXXXX\n";
+    let mut game = theseus::Game::from_board(board)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+    game.set_max_turns(Some(5));
+    assert!(!game.is_lost_cause());
+
+    // One wasted turn still leaves exactly enough
+    game.step(Command::Skip);
+    assert!(!game.is_lost_cause());
+
+    // A second one doesn't
+    assert_eq!(game.step(Command::Skip), GameStatus::Continue);
+    assert!(game.is_lost_cause());
+    assert!(!game.is_winnable());
+
+    // Finished games
+    game.set_max_turns(None);
+    game.replay(&[Command::Right; 4]);
+    assert_eq!(game.status(), GameStatus::Win);
+    assert!(!game.is_lost_cause());
+    let mut game = theseus::Game::from_board(board).unwrap();
+    game.set_max_turns(Some(0));
+    assert_eq!(game.status(), GameStatus::Lose);
+    assert!(game.is_lost_cause());
+}
+
+#[test]
+fn test_diagonal_minotaur() {
+    use theseus::{Command, DiagonalPolicy, GameStatus, Position};
+    let board = "XXXXXXX\n\
+                 XM    X\n\
+                 X     X\n\
+                 X  T  X\n\
+                 X    GX\n\
+                 XXXXXXX\n";
+    let mut orthogonal = theseus::Game::from_board(board)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+    let mut diagonal = orthogonal.clone();
+    diagonal.set_minotaur_policy(DiagonalPolicy);
+
+    assert_eq!(diagonal.step(Command::Skip), GameStatus::Continue);
+    assert_eq!(diagonal.minotaur_position(), Position::new(2, 2));
+    assert_eq!(diagonal.step(Command::Skip), GameStatus::Lose);
+
+    orthogonal.replay(&[Command::Skip, Command::Skip]);
+    assert_eq!(orthogonal.status(), GameStatus::Continue);
+    assert_eq!(orthogonal.minotaur_position(), Position::new(1, 3));
+    assert_eq!(
+        orthogonal.replay(&[Command::Skip; 2]),
+        (GameStatus::Lose, 2)
+    );
+
+    // Walls block diagonal steps like any other
+    let mut game = theseus::Game::from_board(&board.replace("X     X\n", "X X   X\n")).unwrap();
+    game.set_minotaur_policy(DiagonalPolicy);
+    game.step(Command::Skip);
+    assert_ne!(game.minotaur_position(), Position::new(2, 2));
+    assert_eq!(game.minotaur_position(), Position::new(2, 1));
+}
+
+#[test]
+fn test_theseus_diagonal_moves() {
+    use theseus::{Command, Game, GameConfig, Position};
+    let board = "XXXXXX\n\
+                 X  X X\n\
+                 X TX X\n\
+                 XX  GX\n\
+                 XMXXXX\n\
+                 XXXXXX\n";
+    let config = GameConfig {
+        allow_diagonal_moves: true,
+        ..GameConfig::default()
+    };
+    let mut game = Game::from_board_with_config(board, config)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+    assert!(game.diagonal_moves());
+    assert!(game.theseus_move(Command::UpLeft));
+    assert_eq!(game.theseus_position(), Position::new(1, 1));
+    assert!(game.theseus_move(Command::DownRight));
+    assert_eq!(game.theseus_position(), Position::new(2, 2));
+    assert!(game.theseus_move(Command::DownRight));
+    assert_eq!(game.theseus_position(), Position::new(3, 3));
+
+    // Blocked by walls and the edge of the board
+    assert!(!game.theseus_move(Command::DownRight));
+    assert!(!game.theseus_move(Command::DownLeft));
+    assert_eq!(game.theseus_position(), Position::new(3, 3));
+    let mut corner = Game::from_board_with_config("TMG\n", config).unwrap();
+    assert!(!corner.theseus_move(Command::UpLeft));
+    assert!(!corner.theseus_move(Command::DownRight));
+    assert!(game.legal_theseus_moves().contains(&Command::UpLeft));
+
+    // Classic play is unchanged: without the option diagonals don't move him
+    assert_eq!(Command::all().count(), 5);
+    let mut classic = Game::from_board(board).unwrap();
+    assert!(!classic.diagonal_moves());
+    assert!(!classic.theseus_move(Command::UpLeft));
+    assert_eq!(classic.theseus_position(), Position::new(2, 2));
+    assert!(!classic.legal_theseus_moves().contains(&Command::UpLeft));
+
+    for (token, command) in [
+        ("ul", Command::UpLeft),
+        ("UR", Command::UpRight),
+        ("downleft", Command::DownLeft),
+        ("dr", Command::DownRight),
+    ] {
+        assert_eq!(token.parse::<Command>(), Ok(command));
+        assert_eq!(command.to_string().parse::<Command>(), Ok(command));
+        assert_eq!(command.opposite().opposite(), command);
+    }
+    #[cfg(feature = "std-io")]
+    assert_eq!(
+        theseus::read_input(&b"ul\n"[..]),
+        theseus::InputResult::Move(Command::UpLeft)
+    );
+}
+
+#[test]
+fn test_diagonal_analysis() {
+    use theseus::{BoardError, Command, Game, GameConfig, GameStatus};
+    // Only a diagonal step reaches the goal
+    let board = "XXXXX\n\
+                 XTXMX\n\
+                 XXGXX\n\
+                 XXXXX\n";
+    let strict = GameConfig {
+        require_reachable_goal: true,
+        ..GameConfig::default()
+    };
+    assert_eq!(
+        Game::from_board_with_config(board, strict).err(),
+        Some(BoardError::UnreachableGoal)
+    );
+    let mut classic = Game::from_board(board)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+    assert!(!classic.is_winnable());
+    assert_eq!(classic.step(Command::DownRight), GameStatus::Continue);
+
+    let diagonal = GameConfig {
+        allow_diagonal_moves: true,
+        ..strict
+    };
+    let mut game = Game::from_board_with_config(board, diagonal).unwrap();
+    assert!(game.is_winnable());
+    assert_eq!(game.hint(), Some(Command::DownRight));
+    assert_eq!(game.path_distance_to_goal(), Some(1));
+    assert!(game.move_safety().contains(&(Command::DownRight, true)));
+    assert_eq!(game.step(Command::DownRight), GameStatus::Win);
+}
+
+#[test]
+fn test_cell_at() {
+    use theseus::{CellView, Command};
+    let board = "XXXXX\n\
+                 XMT X\n\
+                 X  GX\n\
+                 XXXXX\n";
+    let mut game = theseus::Game::from_board(board)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+    assert_eq!(game.cell_at(0, 0), Some(CellView::Wall));
+    assert_eq!(game.cell_at(1, 1), Some(CellView::Minotaur));
+    assert_eq!(game.cell_at(1, 2), Some(CellView::Theseus));
+    assert_eq!(game.cell_at(1, 3), Some(CellView::Empty));
+    assert_eq!(game.cell_at(2, 3), Some(CellView::Goal));
+    assert_eq!(game.cell_at(4, 0), None);
+    assert_eq!(game.cell_at(0, 5), None);
+
+    // Entities are reported over the goal they stand on
+    game.theseus_move(Command::Down);
+    game.theseus_move(Command::Right);
+    assert_eq!(game.cell_at(2, 3), Some(CellView::Theseus));
+
+    let board = "TMGKD\n\
+                 PPI^7\n";
+    let game = theseus::Game::from_board(board)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+    let row: Vec<Option<CellView>> = (0..5).map(|c| game.cell_at(1, c)).collect();
+    assert_eq!(
+        row,
+        vec![
+            Some(CellView::Portal),
+            Some(CellView::Portal),
+            Some(CellView::Ice),
+            Some(CellView::Trap),
+            Some(CellView::Mud(7)),
+        ]
+    );
+    assert_eq!(game.cell_at(0, 3), Some(CellView::Key));
+    assert_eq!(game.cell_at(0, 4), Some(CellView::Door));
+    assert_eq!(game.cell_at(0, 2), Some(CellView::Goal));
+}
+
+#[test]
+fn test_grid_neighbors() {
+    let board = "T   \n\
+                 X   \n\
+                 MX G\n";
+    let game = theseus::Game::from_board(board)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+    let grid = game.grid();
+    let neighbors = |row, col| grid.neighbors(row, col).collect::<Vec<_>>();
+
+    // Center cell, open on all four sides (up, down, left, right)
+    assert_eq!(neighbors(1, 2), vec![(0, 2), (2, 2), (1, 1), (1, 3)]);
+    // Walled in below and to the left
+    assert_eq!(neighbors(1, 1), vec![(0, 1), (1, 2)]);
+    // Corners and edges only yield cells on the board
+    assert_eq!(neighbors(0, 0), vec![(0, 1)]);
+    assert_eq!(neighbors(0, 3), vec![(1, 3), (0, 2)]);
+    assert_eq!(neighbors(2, 3), vec![(1, 3), (2, 2)]);
+    // Walled in on every side
+    assert_eq!(neighbors(2, 0), vec![]);
+}
+
+#[test]
+fn test_with_positions() {
+    use theseus::{BoardError, GameStatus, Position};
+    let board = "XXXXXX\n\
+                 XT  MX\n\
+                 X X  X\n\
+                 X   GX\n\
+                 XXXXXX\n";
+    let mut game = theseus::Game::from_board(board)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+    game.step(theseus::Command::Right);
+
+    let moved = game.with_positions((3, 1), (1, 1)).unwrap();
+    assert_eq!(moved.theseus_position(), Position::new(3, 1));
+    assert_eq!(moved.minotaur_positions(), &[Position::new(1, 1)]);
+    assert_eq!(moved.turns(), 0);
+    assert_eq!(moved.status(), GameStatus::Continue);
+    assert_eq!(moved.grid(), game.grid());
+    // The original is untouched
+    assert_eq!(game.theseus_position(), Position::new(1, 2));
+
+    // Resetting the copy goes back to the new positions
+    let mut moved = moved;
+    moved.step(theseus::Command::Right);
+    moved.reset();
+    assert_eq!(moved.theseus_position(), Position::new(3, 1));
+
+    assert!(matches!(
+        game.with_positions((2, 2), (1, 4)),
+        Err(BoardError::Occupied { row: 2, col: 2 })
+    ));
+    assert!(matches!(
+        game.with_positions((1, 1), (0, 3)),
+        Err(BoardError::Occupied { row: 0, col: 3 })
+    ));
+    assert!(matches!(
+        game.with_positions((1, 1), (5, 1)),
+        Err(BoardError::OutOfBounds { row: 5, col: 1 })
+    ));
+}
+
+#[test]
+fn test_reachable_cells() {
+    use std::collections::HashSet;
+    let board = "XXXXXXX\n\
+                 XT X  X\n\
+                 X MX GX\n\
+                 XXXXXXX\n";
+    let game = theseus::Game::from_board(board)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+    // The goal's pocket is sealed off; the Minotaur doesn't block anything
+    let expected: HashSet<(usize, usize)> = [(1, 1), (1, 2), (2, 1), (2, 2)].into();
+    assert_eq!(game.reachable_cells(), expected);
+    assert!(!game.reachable_cells().contains(&(2, 5)));
+
+    // A pair of portals joins the two pockets
+    let board = "XXXXXXX\n\
+                 XTPX  X\n\
+                 X MXPGX\n\
+                 XXXXXXX\n";
+    let game = theseus::Game::from_board(board)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+    assert_eq!(game.reachable_cells().len(), 8);
+    assert!(game.reachable_cells().contains(&(2, 5)));
+}
+
+#[test]
+fn test_require_reachable_goal() {
+    use theseus::{BoardError, Game, GameConfig};
+    let strict = GameConfig {
+        require_reachable_goal: true,
+        ..GameConfig::default()
+    };
+    let reachable = "XXXXXX\n\
+                     XT M X\n\
+                     XX X X\n\
+                     X   GX\n\
+                     XXXXXX\n";
+    assert!(Game::from_board_with_config(reachable, strict).is_ok());
+
+    // The Minotaur doesn't count, only walls
+    let blocked_by_minotaur = "XXXXX\n\
+                               XTMGX\n\
+                               XXXXX\n";
+    assert!(Game::from_board_with_config(blocked_by_minotaur, strict).is_ok());
+
+    let walled_off = "XXXXXX\n\
+                      XT MXX\n\
+                      XXXX X\n\
+                      X   GX\n\
+                      XXXXXX\n";
+    assert_eq!(
+        Game::from_board_with_config(walled_off, strict).err(),
+        Some(BoardError::UnreachableGoal)
+    );
+    // Off by default
+    assert!(Game::from_board(walled_off).is_ok());
+}
+
+#[test]
+fn test_autopilot_move() {
+    use theseus::{Command, GameStatus};
+    let board = "XXXXXXXX\n\
+                 XT     X\n\
+                 X XX X X\n\
+                 X    XMX\n\
+                 X XXX  X\n\
+                 X     GX\n\
+                 XXXXXXXX\n";
+    let play = |seed: u64| {
+        let mut game = theseus::Game::from_board(board)
+            .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+        let mut seed = seed;
+        let mut commands = Vec::new();
+        for _ in 0..30 {
+            if game.status() != GameStatus::Continue {
+                break;
+            }
+            let command = game.autopilot_move(&mut seed);
+            let safe_exists = game.legal_theseus_moves().into_iter().any(|c| {
+                let mut trial = game.clone();
+                trial.step(c) != GameStatus::Lose
+            });
+            let status = game.step(command);
+            if safe_exists {
+                assert_ne!(status, GameStatus::Lose, "{} lost the game", command);
+            }
+            commands.push(command);
+        }
+        commands
+    };
+    let first = play(42);
+    assert_eq!(first, play(42));
+    assert!(!first.is_empty());
+    assert!(first.iter().any(|&c| c != Command::Skip));
+    assert_ne!(first, play(7));
+
+    // Every move loses: Theseus is stuck in a dead end next to the Minotaur
+    let board = "XXXXXX\n\
+                 XTM GX\n\
+                 XXXXXX\n";
+    let game = theseus::Game::from_board(board).unwrap();
+    let mut seed = 1;
+    assert_eq!(game.autopilot_move(&mut seed), Command::Skip);
+}
+
+#[test]
+fn test_minotaur_adjacent() {
+    use theseus::Command;
+    let board = "XXXXX\n\
+                 XT  X\n\
+                 X  MX\n\
+                 X  GX\n\
+                 XXXXX\n";
+    let mut game = theseus::Game::from_board(board)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+    assert!(!game.minotaur_adjacent());
+
+    // Diagonal, Manhattan distance 2
+    game.theseus_move(Command::Right);
+    assert!(!game.minotaur_adjacent());
+
+    game.theseus_move(Command::Right);
+    assert!(game.minotaur_adjacent());
+
+    // Same cell
+    game.theseus_move(Command::Down);
+    assert_eq!(game.theseus_position(), game.minotaur_position());
+    assert!(!game.minotaur_adjacent());
+}
+
+#[cfg(feature = "std-io")]
+#[test]
+fn test_arrow_key_input() {
+    use theseus::{read_input, Command, InputResult};
+    assert_eq!(read_input(&b"\x1b[A\n"[..]), InputResult::Move(Command::Up));
+    assert_eq!(
+        read_input(&b"\x1b[B\n"[..]),
+        InputResult::Move(Command::Down)
+    );
+    assert_eq!(
+        read_input(&b"\x1b[C\n"[..]),
+        InputResult::Move(Command::Right)
+    );
+    assert_eq!(
+        read_input(&b"\x1b[D\n"[..]),
+        InputResult::Move(Command::Left)
+    );
+    // Application cursor mode
+    assert_eq!(
+        read_input(&b"\x1bOA\r\n"[..]),
+        InputResult::Move(Command::Up)
+    );
+    assert_eq!("\x1bOD".parse::<Command>(), Ok(Command::Left));
+    assert_eq!(
+        read_input(&b"\x1b[E\n"[..]),
+        InputResult::Invalid("\x1b[E".to_string())
+    );
+}
+
+#[cfg(feature = "std-io")]
+#[test]
+fn test_input_batch() {
+    use theseus::{input_batch, Command};
+    assert_eq!(
+        input_batch(&b"ddsw\n"[..]),
+        vec![Command::Right, Command::Right, Command::Down, Command::Up]
+    );
+    assert_eq!(
+        input_batch(&b" a . D\n"[..]),
+        vec![Command::Left, Command::Skip, Command::Right]
+    );
+    // Quitting cuts the batch short; unknown characters are dropped
+    assert_eq!(
+        input_batch(&b"dxsqww\n"[..]),
+        vec![Command::Right, Command::Down]
+    );
+    assert_eq!(input_batch(&b""[..]), vec![]);
+
+    // Arrow keys are whole sequences, not the letters inside them
+    assert_eq!(
+        input_batch(&b"\x1b[A\x1b[Dd\x1bOB\n"[..]),
+        vec![Command::Up, Command::Left, Command::Right, Command::Down]
+    );
+    assert_eq!(input_batch(&b"\x1b[Ew\n"[..]), vec![Command::Up]);
+}
+
+#[test]
+fn test_board_size_errors() {
+    use theseus::{BoardError, Game};
+    assert_eq!(Game::from_board("").err(), Some(BoardError::EmptyBoard));
+    assert_eq!(Game::from_board("\n\n").err(), Some(BoardError::EmptyBoard));
+    assert_eq!(
+        Game::from_board("\nTMG\n").err(),
+        Some(BoardError::EmptyFirstRow)
+    );
+    let ragged = "XXXX\n\
+                  XTMX\n\
+                  XG\n\
+                  XXXX\n";
+    let err = Game::from_board(ragged).err();
+    assert_eq!(
+        err,
+        Some(BoardError::RowLengthMismatch {
+            row: 2,
+            expected: 4,
+            found: 2
+        })
+    );
+    assert_eq!(
+        err.unwrap().to_string(),
+        "Row 2 is 2 wide, but the first row is 4 wide"
+    );
+}
+
+#[test]
+fn test_tile_counts() {
+    use theseus::TileCounts;
+    let board = "XXXXXX\n\
+                 XT K X\n\
+                 X XXMX\n\
+                 XM ^GX\n\
+                 XXXXXX\n";
+    let game = theseus::Game::from_board(board)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+    assert_eq!(
+        game.tile_counts(),
+        TileCounts {
+            walls: 20,
+            empty: 7,
+            special: 2,
+            has_goal: true,
+            has_theseus: true,
+            minotaurs: 2,
+        }
+    );
+}
+
+#[test]
+fn test_wrap_around_edges() {
+    use theseus::{Command, Game, GameConfig, GameStatus, Position};
+    let board = "XXXXXXX\n\
+                 T  M  G\n\
+                 XXXXXXX\n";
+    let config = GameConfig {
+        wrap: true,
+        ..GameConfig::default()
+    };
+    let mut game = Game::from_board_with_config(board, config)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+    assert!(game.wrap());
+    // Walking off the left edge lands on the right one, which wins here
+    assert!(game.theseus_move(Command::Left));
+    assert_eq!(game.theseus_position(), Position::new(1, 6));
+    assert_eq!(game.status(), GameStatus::Win);
+
+    // Walls still block across the edge
+    let mut game = Game::from_board_with_config(board, config).unwrap();
+    assert!(!game.theseus_move(Command::Up));
+    assert_eq!(game.theseus_position(), Position::new(1, 0));
+
+    // Without wrapping the edge stops him
+    let mut game = Game::from_board(board).unwrap();
+    assert!(!game.theseus_move(Command::Left));
+
+    // The Minotaur chases the short way round
+    let board = "XXXXXXXX\n\
+                 T   GM  \n\
+                 XXXXXXXX\n";
+    let mut game = Game::from_board_with_config(board, config).unwrap();
+    assert_eq!(game.theseus_minotaur_distance(), 3);
+    game.step(Command::Skip);
+    assert_eq!(game.minotaur_position(), Position::new(1, 6));
+    game.set_wrap(false);
+    game.step(Command::Skip);
+    assert_eq!(game.minotaur_position(), Position::new(1, 5));
+}
+
+#[test]
+fn test_wrap_reachable_goal() {
+    use theseus::{Game, GameConfig};
+    // The goal is only reachable by walking off the left edge
+    let board = "XXXXXXX\n\
+                 T XM  G\n\
+                 XXXXXXX\n";
+    let config = GameConfig {
+        wrap: true,
+        require_reachable_goal: true,
+        ..GameConfig::default()
+    };
+    let game = Game::from_board_with_config(board, config)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+    assert!(game.reachable_cells().contains(&(1, 6)));
+    assert_eq!(game.path_distance_to_goal(), Some(1));
+}
+
+#[test]
+fn test_wrap_ice_loop() {
+    use theseus::{Command, Game, GameConfig, Position};
+    let board = "IIIII\n\
+                 T   G\n\
+                 M    \n";
+    let config = GameConfig {
+        wrap: true,
+        ..GameConfig::default()
+    };
+    let game = Game::from_board_with_config(board, config)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+    // A whole row of ice slides him all the way round, back where he started
+    let mut game = game.with_positions((0, 0), (2, 0)).unwrap();
+    assert!(game.theseus_move(Command::Right));
+    assert_eq!(game.theseus_position(), Position::new(0, 0));
+}
+
+#[test]
+fn test_line_of_sight_policy() {
+    use theseus::{Command, LineOfSightPolicy, Position};
+    let board = "XXXXXXX\n\
+                 XT   MX\n\
+                 X X X X\n\
+                 X    GX\n\
+                 XXXXXXX\n";
+    let mut game = theseus::Game::from_board(board)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+    let policy = LineOfSightPolicy::default();
+    game.set_minotaur_policy(policy);
+
+    // Same row, nothing in between: it chases
+    assert!(policy.sees(&game, game.minotaur_position()));
+    game.step(Command::Skip);
+    assert_eq!(game.minotaur_position(), Position::new(1, 4));
+
+    // Out of sight around the corner, it stays put
+    game.step(Command::Down);
+    assert!(!policy.sees(&game, game.minotaur_position()));
+    assert_eq!(game.minotaur_position(), Position::new(1, 4));
+    game.step(Command::Skip);
+    assert_eq!(game.minotaur_position(), Position::new(1, 4));
+
+    // Same column, but a wall breaks the line of sight
+    let board = "XXXXX\n\
+                 XM  X\n\
+                 XX  X\n\
+                 XT GX\n\
+                 XXXXX\n";
+    let mut game = theseus::Game::from_board(board).unwrap();
+    game.set_minotaur_policy(policy);
+    assert!(!policy.sees(&game, game.minotaur_position()));
+    game.step(Command::Skip);
+    assert_eq!(game.minotaur_position(), Position::new(1, 1));
+
+    // A short sight range hides Theseus even in plain view
+    let board = "XXXXXXX\n\
+                 XT   MX\n\
+                 X    GX\n\
+                 XXXXXXX\n";
+    let mut game = theseus::Game::from_board(board).unwrap();
+    let short = LineOfSightPolicy { range: Some(3) };
+    game.set_minotaur_policy(short);
+    game.step(Command::Skip);
+    assert_eq!(game.minotaur_position(), Position::new(1, 5));
+    game.step(Command::Right);
+    assert_eq!(game.minotaur_position(), Position::new(1, 4));
+}
+
+#[test]
+fn test_apply_outcome() {
+    use theseus::{Command, GameStatus, TurnOutcome};
+    let board = "XXXXXX\n\
+                 XT  MX\n\
+                 X XX X\n\
+                 X   GX\n\
+                 XXXXXX\n";
+    let mut game = theseus::Game::from_board(board)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+
+    // Theseus walks into a wall, but the Minotaur still comes for him
+    assert_eq!(
+        game.apply(Command::Up),
+        TurnOutcome {
+            theseus_moved: false,
+            minotaur_moved: true,
+            status: GameStatus::Continue,
+        }
+    );
+    assert_eq!(game.turns(), 1);
+
+    assert_eq!(
+        game.apply(Command::Down),
+        TurnOutcome {
+            theseus_moved: true,
+            minotaur_moved: true,
+            status: GameStatus::Continue,
+        }
+    );
+    // Blocked by the wall between them, the Minotaur stays put
+    let board = "XXXXX\n\
+                 XTXMX\n\
+                 X   X\n\
+                 X  GX\n\
+                 XXXXX\n";
+    let mut game = theseus::Game::from_board(board).unwrap();
+    assert_eq!(
+        game.apply(Command::Skip),
+        TurnOutcome {
+            theseus_moved: true,
+            minotaur_moved: false,
+            status: GameStatus::Continue,
+        }
+    );
+}
+
+#[test]
+fn test_board_positions() {
+    let board = "XTX\n\
+                 M G\n";
+    let game = theseus::Game::from_board(board)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+    let positions: Vec<(usize, usize)> = game.positions().collect();
+    assert_eq!(positions.len(), game.width() * game.height());
+    assert_eq!(
+        positions,
+        vec![(0, 0), (0, 1), (0, 2), (1, 0), (1, 1), (1, 2)]
+    );
+    assert!(positions
+        .iter()
+        .all(|&(row, col)| game.cell_at(row, col).is_some()));
+}
+
+#[test]
+fn test_minotaur_next_move() {
+    use theseus::{Axis, Command, DiagonalPolicy, Game, Position};
+    let check = |mut game: Game, expected: Command| {
+        let before = game.minotaur_position();
+        let predicted = game.minotaur_next_move();
+        assert_eq!(predicted, expected);
+        // Predicting doesn't move anything
+        assert_eq!(game.minotaur_position(), before);
+        game.minotaur_move();
+        assert_eq!(Some(game.minotaur_position()), before.step(predicted));
+    };
+    let board = "XXXXXX\n\
+                 XT   X\n\
+                 X  X X\n\
+                 X   MX\n\
+                 X G  X\n\
+                 XXXXXX\n";
+    let game = Game::from_board(board)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+    check(game.clone(), Command::Left);
+
+    let mut vertical = game.clone();
+    vertical.set_minotaur_axis_priority(Axis::VerticalFirst);
+    check(vertical, Command::Up);
+
+    let mut diagonal = game.with_positions((1, 1), (4, 4)).unwrap();
+    diagonal.set_minotaur_policy(DiagonalPolicy);
+    check(diagonal, Command::UpLeft);
+
+    // Walled in between Theseus and itself, it waits
+    let blocked = game.with_positions((1, 3), (3, 3)).unwrap();
+    assert_eq!(blocked.minotaur_position(), Position::new(3, 3));
+    check(blocked, Command::Skip);
+}
+
+#[test]
+fn test_json_round_trip() {
+    use theseus::{BoardError, Command, Game, Position};
+    let board = "XXXXXXX\n\
+                 XTK   X\n\
+                 XP X PX\n\
+                 X ^ MGX\n\
+                 XXXXXXX\n";
+    let mut game = Game::from_board(board)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+    game.step(Command::Right);
+    assert!(game.has_key());
+
+    let json = game.to_json();
+    assert_eq!(
+        json,
+        concat!(
+            r#"{"width":7,"height":5,"#,
+            r#""rows":["XXXXXXX","X K   X","XP X PX","X ^  GX","XXXXXXX"],"#,
+            r#""theseus":[1,2],"minotaurs":[[3,3]],"goal":[3,5],"has_key":true,"turn":1}"#
+        )
+    );
+    let loaded = Game::from_json(&json).unwrap();
+    assert!(loaded == game);
+    assert_eq!(loaded.turns(), 1);
+    assert_eq!(loaded.to_json(), json);
+
+    // Whitespace is fine, and the optional fields can be left out
+    let loaded =
+        Game::from_json(r#"{ "rows": ["   G"], "theseus": [0, 0], "minotaurs": [[0, 1]] }"#)
+            .unwrap();
+    assert_eq!(loaded.goal_position(), Position::new(0, 3));
+    assert_eq!(loaded.turns(), 0);
+
+    assert!(matches!(
+        Game::from_json(r#"{"rows":["   G"],"theseus":[0,0]"#),
+        Err(BoardError::InvalidJson)
+    ));
+    assert!(matches!(
+        Game::from_json(r#"{"rows":["X  G"],"theseus":[0,0],"minotaurs":[[0,1]]}"#),
+        Err(BoardError::Occupied { row: 0, col: 0 })
+    ));
+    assert!(matches!(
+        Game::from_json(r#"{"rows":["  G","G  "],"theseus":[0,0],"minotaurs":[[0,1]]}"#),
+        Err(BoardError::MultipleGoal)
+    ));
+}
+
+#[test]
+fn test_multiple_goals() {
+    use theseus::{BoardError, Command, Game, GameConfig, GameStatus, Position};
+    let board = "XXXXXXX\n\
+                 XG T GX\n\
+                 X XXX X\n\
+                 X    MX\n\
+                 XXXXXXX\n";
+    assert_eq!(
+        Game::from_board(board).err(),
+        Some(BoardError::MultipleGoal)
+    );
+
+    let config = GameConfig {
+        allow_multiple_goals: true,
+        ..GameConfig::default()
+    };
+    let mut game = Game::from_board_with_config(board, config)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+    assert_eq!(
+        game.goals_left(),
+        &[Position::new(1, 1), Position::new(1, 5)]
+    );
+
+    // Reaching one goal isn't enough
+    assert_eq!(game.step(Command::Right), GameStatus::Continue);
+    assert_eq!(game.step(Command::Right), GameStatus::Continue);
+    assert_eq!(game.goals_left(), &[Position::new(1, 1)]);
+    // Then all the way back to the other one
+    game.step(Command::Left);
+    assert_eq!(game.step(Command::Left), GameStatus::Continue);
+    assert_eq!(game.step(Command::Left), GameStatus::Continue);
+    assert_eq!(game.step(Command::Left), GameStatus::Win);
+    assert!(game.goals_left().is_empty());
+
+    // Undo brings the goal back
+    game.undo();
+    assert_eq!(game.goals_left(), &[Position::new(1, 1)]);
+    assert_eq!(game.status(), GameStatus::Continue);
+
+    let solution = Game::from_board_with_config(board, config)
+        .unwrap()
+        .solve()
+        .unwrap();
+    assert_eq!(solution.len(), 6);
+
+    // One goal still works in multi-goal mode
+    let single = "TMG\n";
+    let mut game = Game::from_board_with_config(single, config).unwrap();
+    assert_eq!(game.goals_left(), &[Position::new(0, 2)]);
+    assert_eq!(game.step(Command::Skip), GameStatus::Lose);
+    // In single-goal mode there's nothing to collect
+    assert!(Game::from_board(single).unwrap().goals_left().is_empty());
+}
+
+#[test]
+fn test_set_cell_multiple_goals() {
+    use theseus::{BoardError, CellView, Command, Game, GameConfig};
+    let board = "XXXXXXX\n\
+                 XG T GX\n\
+                 X XXX X\n\
+                 X    MX\n\
+                 XXXXXXX\n";
+    let config = GameConfig {
+        allow_multiple_goals: true,
+        ..GameConfig::default()
+    };
+    let mut game = Game::from_board_with_config(board, config)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+    // Every goal still to visit is protected, not just the first
+    assert_eq!(game.set_cell(1, 5, 'X'), Err(BoardError::NoGoal));
+    assert_eq!(game.set_cell(1, 1, ' '), Err(BoardError::NoGoal));
+    assert_eq!(game.set_cell(1, 5, 'G'), Ok(()));
+    assert_eq!(game.set_cell(1, 2, 'G'), Err(BoardError::MultipleGoal));
+
+    // A visited goal is drawn as an empty tile, and can be built over
+    game.step(Command::Right);
+    game.step(Command::Right);
+    game.step(Command::Left);
+    assert_eq!(game.cell_at(1, 5), Some(CellView::Empty));
+    assert!(!game.is_goal(1, 5));
+    assert_eq!(game.to_board().lines().nth(1), Some("XG  T X"));
+    assert_eq!(game.set_cell(1, 5, 'X'), Ok(()));
+}
+
+#[test]
+fn test_json_multiple_goals() {
+    use theseus::{BoardError, Command, Game, GameConfig, Position};
+    let board = "XXXXXXX\n\
+                 XG T GX\n\
+                 X XXX X\n\
+                 X    MX\n\
+                 XXXXXXX\n";
+    let config = GameConfig {
+        allow_multiple_goals: true,
+        ..GameConfig::default()
+    };
+    let mut game = Game::from_board_with_config(board, config)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+    game.step(Command::Right);
+    game.step(Command::Right);
+    assert_eq!(game.goals_left(), &[Position::new(1, 1)]);
+
+    let json = game.to_json();
+    assert!(json.ends_with(r#""goals_left":[[1,1]]}"#));
+    let loaded = Game::from_json_with_config(&json, config).unwrap();
+    assert_eq!(loaded.goals_left(), &[Position::new(1, 1)]);
+    assert!(loaded == game);
+    assert_eq!(loaded.to_json(), json);
+    assert_eq!(Game::from_json(&json).err(), Some(BoardError::MultipleGoal));
+
+    // Left out, every goal is still to visit
+    let fresh = json.replace(r#","goals_left":[[1,1]]"#, "");
+    let loaded = Game::from_json_with_config(&fresh, config).unwrap();
+    assert_eq!(
+        loaded.goals_left(),
+        &[Position::new(1, 1), Position::new(1, 5)]
+    );
+}
+
+#[test]
+fn test_tick() {
+    use theseus::{Command, GameStatus, Position};
+    let board = "XXXXXXXXXX\n\
+                 XT      MX\n\
+                 X XXXXXX X\n\
+                 X       GX\n\
+                 XXXXXXXXXX\n";
+    let mut game = theseus::Game::from_board(board)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+    game.step(Command::Right);
+    let mut ticks = 0;
+    while game.tick() == GameStatus::Continue {
+        ticks += 1;
+        assert!(ticks < 10, "the Minotaur never caught up");
+    }
+    assert_eq!(game.status(), GameStatus::Lose);
+    assert_eq!(game.minotaur_position(), Position::new(1, 2));
+    assert_eq!(ticks, 4);
+    // Ticks aren't turns
+    assert_eq!(game.turns(), 1);
+    assert_eq!(game.tick(), GameStatus::Lose);
+
+    game.undo();
+    assert_eq!(game.theseus_position(), Position::new(1, 1));
+    assert_eq!(game.minotaur_position(), Position::new(1, 8));
+}
+
+#[test]
+fn test_validate_reports_every_problem() {
+    use theseus::{BoardError, Game};
+    let board = "XXXXXX\n\
+                 X?T  X\n\
+                 XPX\n\
+                 X T GG\n\
+                 XX!XXX\n";
+    assert_eq!(
+        Game::validate(board),
+        vec![
+            BoardError::InvalidCharacter {
+                ch: '?',
+                row: 1,
+                col: 1
+            },
+            BoardError::RowLengthMismatch {
+                row: 2,
+                expected: 6,
+                found: 3
+            },
+            BoardError::InvalidCharacter {
+                ch: '!',
+                row: 4,
+                col: 2
+            },
+            BoardError::MultipleTheseus,
+            BoardError::NoMinotaur,
+            BoardError::MultipleGoal,
+            BoardError::UnpairedPortal,
+        ]
+    );
+    // from_board only sees the first of them
+    assert_eq!(
+        Game::from_board(board).err(),
+        Some(Game::validate(board)[0])
+    );
+
+    assert!(Game::validate("TMG\n").is_empty());
+    assert_eq!(Game::validate(""), vec![BoardError::EmptyBoard]);
+
+    let too_wide = format!("TMG{}\n", " ".repeat(1097));
+    assert_eq!(
+        Game::validate(&too_wide),
+        vec![BoardError::TooLarge {
+            width: 1100,
+            height: 1
+        }]
+    );
+    assert_eq!(
+        Game::from_board(&too_wide).err(),
+        Some(Game::validate(&too_wide)[0])
+    );
+}
+
+#[test]
+fn test_render_diff() {
+    use theseus::{Command, WALL_GLYPH};
+    let board = "XXXXX\n\
+                 XT  X\n\
+                 XXX X\n\
+                 XM GX\n\
+                 XXXXX\n";
+    let game = theseus::Game::from_board(board)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+    assert!(game.render_diff(&game).is_empty());
+
+    let mut moved = game.clone();
+    moved.theseus_move(Command::Right);
+    assert_eq!(moved.render_diff(&game), vec![(1, 1, ' '), (1, 2, 'T')]);
+    assert_eq!(game.render_diff(&moved), vec![(1, 1, 'T'), (1, 2, ' ')]);
+
+    // A smaller previous board leaves the rest to draw
+    let small = theseus::Game::from_board("XXXXX\nXT  X\nXM GX\n").unwrap();
+    let diff = game.render_diff(&small);
+    assert_eq!(diff[0], (2, 1, WALL_GLYPH));
+    assert!(diff.contains(&(4, 4, WALL_GLYPH)));
+}
+
+#[test]
+fn test_nearest_safe_cell() {
+    let board = "XXXXXXXXX\n\
+                 X   T M X\n\
+                 X XXXXX X\n\
+                 X   G   X\n\
+                 XXXXXXXXX\n";
+    let game = theseus::Game::from_board(board)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+    // Heading right towards the goal walks into the Minotaur's next step
+    assert_eq!(game.nearest_safe_cell(), Some((1, 3)));
+
+    // Already out of reach, Theseus can stay where he is
+    let far = game.with_positions((3, 2), (1, 7)).unwrap();
+    assert_eq!(far.nearest_safe_cell(), Some((3, 2)));
+
+    // Cornered, with the Minotaur in the only way out
+    let board = "XXXXX\n\
+                 XTM X\n\
+                 XXXGX\n\
+                 XXXXX\n";
+    let game = theseus::Game::from_board(board).unwrap();
+    assert_eq!(game.nearest_safe_cell(), None);
+}
+
+#[test]
+fn test_nearest_safe_cell_fast_minotaur() {
+    let board = "XXXXXXXXX\n\
+                 XM  T   X\n\
+                 XXXXXXXGX\n";
+    let mut game = theseus::Game::from_board(board)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+    assert_eq!(game.nearest_safe_cell(), Some((1, 4)));
+    // Two steps bring the Minotaur right up to Theseus, so he has to back off
+    game.set_minotaur_speed(2);
+    assert_eq!(game.nearest_safe_cell(), Some((1, 5)));
+    game.step(theseus::Command::Skip);
+    assert_eq!(game.minotaur_position(), theseus::Position::new(1, 3));
+}
+
+#[test]
+fn test_rotate_and_mirror() {
+    use theseus::{Command, Game, Position};
+    let board = "XXXXXX\n\
+                 XT   X\n\
+                 X XX X\n\
+                 X  MGX\n\
+                 XXXXXX\n";
+    let game = Game::from_board(board)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+    let solution = game.solve().expect("board should be winnable");
+
+    let rotated = game.rotated_90();
+    assert_eq!((rotated.width(), rotated.height()), (5, 6));
+    assert_eq!(
+        rotated.to_board(),
+        "XXXXX\n\
+         X  TX\n\
+         X X X\n\
+         XMX X\n\
+         XG  X\n\
+         XXXXX\n"
+    );
+    assert_eq!(rotated.theseus_position(), Position::new(1, 3));
+    assert_eq!(rotated.minotaur_position(), Position::new(3, 1));
+    assert_eq!(rotated.goal_position(), Position::new(4, 1));
+
+    let mirrored = game.mirrored_horizontal();
+    assert_eq!(mirrored.theseus_position(), Position::new(1, 4));
+    assert_eq!(mirrored.goal_position(), Position::new(3, 1));
+    assert_eq!(
+        mirrored.to_board(),
+        "XXXXXX\n\
+         X   TX\n\
+         X XX X\n\
+         XGM  X\n\
+         XXXXXX\n"
+    );
+    assert_eq!(
+        game.mirrored_vertical().theseus_position(),
+        Position::new(3, 1)
+    );
+    assert_eq!(game.rotated_180().theseus_position(), Position::new(3, 4));
+    assert_eq!(game.rotated_270().theseus_position(), Position::new(4, 1));
+
+    // A full turn round, or flipping twice, is the same game
+    let round = game.rotated_90().rotated_90().rotated_90().rotated_90();
+    assert!(round == game);
+    assert_eq!(
+        round.minotaur_axis_priority(),
+        game.minotaur_axis_priority()
+    );
+    assert!(game.rotated_90().rotated_270() == game);
+    assert!(game.mirrored_horizontal().mirrored_horizontal() == game);
+
+    // Every variant still loads and plays the same
+    for variant in [
+        game.rotated_90(),
+        game.rotated_180(),
+        game.rotated_270(),
+        game.mirrored_horizontal(),
+        game.mirrored_vertical(),
+    ] {
+        let reloaded = Game::from_board(&variant.to_board()).unwrap();
+        assert!(reloaded.is_winnable());
+        assert_eq!(variant.solve().map(|s| s.len()), Some(solution.len()));
+    }
+
+    // History carries over
+    let mut played = game.clone();
+    played.step(Command::Right);
+    let mut rotated = played.rotated_90();
+    assert!(rotated.undo());
+    assert_eq!(rotated.theseus_position(), Position::new(1, 3));
+}
+
+#[test]
+fn test_board_too_large() {
+    use theseus::{BoardError, Game, GameConfig};
+    let config = GameConfig {
+        max_dimension: 4,
+        ..GameConfig::default()
+    };
+    let at_limit = "TM G\n\
+                    X  X\n\
+                    X  X\n\
+                    XXXX\n";
+    assert!(Game::from_board_with_config(at_limit, config).is_ok());
+    let too_wide = "TM  G\n";
+    assert_eq!(
+        Game::from_board_with_config(too_wide, config).err(),
+        Some(BoardError::TooLarge {
+            width: 5,
+            height: 1
+        })
+    );
+    let too_tall = format!("{}XXXX\n", at_limit);
+    assert_eq!(
+        Game::from_board_with_config(&too_tall, config).err(),
+        Some(BoardError::TooLarge {
+            width: 4,
+            height: 5
+        })
+    );
+    // The default limit leaves ordinary boards alone
+    assert!(Game::from_board(too_wide).is_ok());
+
+    // The other constructors check before making room for the board
+    assert_eq!(
+        Game::from_board_sized("TMG", usize::MAX, 1).err(),
+        Some(BoardError::TooLarge {
+            width: usize::MAX,
+            height: 1
+        })
+    );
+    assert_eq!(
+        theseus::GameBuilder::new(5, 1100).build().err(),
+        Some(BoardError::TooLarge {
+            width: 5,
+            height: 1100
+        })
+    );
+}
+
+#[test]
+#[should_panic(expected = "at most 1024x1024")]
+fn test_generate_too_large() {
+    theseus::Game::generate(1100, 5, 1);
+}
+
+#[test]
+fn test_all_optimal_solutions() {
+    use theseus::Command::{Down, Left, Right};
+    let board = "XXXXXXX\n\
+                 X T XMX\n\
+                 X X XXX\n\
+                 X G XXX\n\
+                 XXXXXXX\n";
+    let game = theseus::Game::from_board(board)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+    let solutions = game.all_optimal_solutions(10);
+    assert_eq!(
+        solutions,
+        vec![vec![Left, Down, Down, Right], vec![Right, Down, Down, Left]]
+    );
+    for solution in &solutions {
+        assert_eq!(game.clone().replay(solution).0, theseus::GameStatus::Win);
+    }
+    assert_eq!(game.all_optimal_solutions(1).len(), 1);
+    assert!(game.all_optimal_solutions(0).is_empty());
+
+    let lost = "XXXXX\n\
+                XTMGX\n\
+                XXXXX\n";
+    let game = theseus::Game::from_board(lost).unwrap();
+    assert!(game.all_optimal_solutions(10).is_empty());
+}
+
+#[test]
+fn test_trace() {
+    use theseus::{Command, GameStatus, Position, TurnEvent};
+    let board = "XXXXXX\n\
+                 XT  MX\n\
+                 X XX X\n\
+                 X   GX\n\
+                 XXXXXX\n";
+    let mut game = theseus::Game::from_board(board)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+    game.step(Command::Skip);
+    assert!(game.trace().is_empty());
+
+    game.set_tracing(true);
+    game.step(Command::Down);
+    game.step(Command::Down);
+    game.step(Command::Right);
+    let turn = |command, theseus: (usize, usize), minotaur: (usize, usize), status| TurnEvent {
+        command,
+        theseus: theseus.into(),
+        minotaurs: vec![Position::from(minotaur)],
+        status,
+    };
+    assert_eq!(
+        game.trace(),
+        &[
+            turn(Command::Down, (2, 1), (1, 2), GameStatus::Continue),
+            turn(Command::Down, (3, 1), (1, 1), GameStatus::Continue),
+            turn(Command::Right, (3, 2), (1, 2), GameStatus::Continue),
+        ]
+    );
+
+    game.set_tracing(false);
+    assert!(game.trace().is_empty());
+}
+
+#[test]
+fn test_move_safety() {
+    use theseus::Command;
+    let board = "XXXXXXX\n\
+                 XXX XXX\n\
+                 X  T MX\n\
+                 XXX XXX\n\
+                 XXXGXXX\n";
+    let game = theseus::Game::from_board(board)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+    assert_eq!(
+        game.move_safety(),
+        vec![
+            (Command::Up, true),
+            (Command::Down, true),
+            (Command::Left, true),
+            (Command::Right, false),
+            (Command::Skip, true),
+        ]
+    );
+    // Simulating leaves the game alone
+    assert_eq!(game.turns(), 0);
+    assert_eq!(game.theseus_position(), theseus::Position::new(2, 3));
+}
+
+#[test]
+fn test_grid_from_str() {
+    use theseus::{BoardError, Grid};
+    let text = "XXXX\n\
+                X  X\n\
+                X GX\n\
+                XXXX\n";
+    let grid: Grid = text.parse().unwrap();
+    assert_eq!((grid.width(), grid.height()), (4, 4));
+    assert!(grid.is_wall(0, 0));
+    assert!(grid.is_goal(2, 2));
+    assert_eq!(
+        grid.neighbors(1, 1).collect::<Vec<_>>(),
+        vec![(2, 1), (1, 2)]
+    );
+
+    // The same tiles as a game's grid, and different from another layout
+    let game = theseus::Game::from_board("XXXX\nXT X\nXMGX\nXXXX\n").unwrap();
+    assert_eq!(&grid, game.grid());
+    assert_eq!(text.parse::<Grid>(), Ok(grid.clone()));
+    assert_ne!("XXXX\nX XX\nX GX\nXXXX\n".parse::<Grid>(), Ok(grid));
+
+    assert_eq!(
+        "XX\nXT\n".parse::<Grid>(),
+        Err(BoardError::InvalidCharacter {
+            ch: 'T',
+            row: 1,
+            col: 1
+        })
+    );
+    assert_eq!("".parse::<Grid>(), Err(BoardError::EmptyBoard));
+}
+
+#[test]
+fn test_transcript_round_trip() {
+    use theseus::{decode_transcript, encode_transcript, Command};
+    let commands = vec![
+        Command::Up,
+        Command::Down,
+        Command::Left,
+        Command::Right,
+        Command::Skip,
+        Command::UpLeft,
+        Command::UpRight,
+        Command::DownLeft,
+        Command::DownRight,
+        Command::Right,
+    ];
+    let transcript = encode_transcript(&commands);
+    assert_eq!(transcript, "udlr.7913r");
+    assert_eq!(decode_transcript(&transcript), Ok(commands));
+    assert_eq!(decode_transcript(""), Ok(vec![]));
+
+    assert_eq!(
+        decode_transcript("ddx."),
+        Err("Unknown command: x (char 2)".to_string())
+    );
+}
+
+#[test]
+fn test_clumsy_policy() {
+    use theseus::{ClumsyPolicy, Command, Game, Position};
+    let board = "XXXXXXXXX\n\
+                 XT      X\n\
+                 X       X\n\
+                 X       X\n\
+                 X      MX\n\
+                 X G     X\n\
+                 XXXXXXXXX\n";
+    let play = |policy: Option<ClumsyPolicy>| {
+        let mut game = Game::from_board(board)
+            .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+        if let Some(policy) = policy {
+            game.set_minotaur_policy(policy);
+        }
+        let mut path: Vec<Position> = Vec::new();
+        for _ in 0..4 {
+            game.step(Command::Skip);
+            path.push(game.minotaur_position());
+        }
+        path
+    };
+    let greedy = play(None);
+    assert_eq!(play(Some(ClumsyPolicy::default())), greedy);
+    assert_eq!(play(Some(ClumsyPolicy::new(0.0, 99))), greedy);
+
+    let clumsy = play(Some(ClumsyPolicy::new(1.0, 7)));
+    assert_ne!(clumsy, greedy);
+    assert_eq!(play(Some(ClumsyPolicy::new(1.0, 7))), clumsy);
+
+    // Looking ahead doesn't use up any of its mistakes
+    let mut plain = Game::from_board(board).unwrap();
+    plain.set_minotaur_policy(ClumsyPolicy::new(0.5, 7));
+    let mut hinted = Game::from_board(board).unwrap();
+    hinted.set_minotaur_policy(ClumsyPolicy::new(0.5, 7));
+    for _ in 0..4 {
+        hinted.hint();
+        hinted.move_safety();
+        hinted.minotaur_next_move();
+        plain.step(Command::Skip);
+        hinted.step(Command::Skip);
+        assert_eq!(hinted.minotaur_position(), plain.minotaur_position());
+    }
+}
+
+#[test]
+fn test_theseus_cornered() {
+    let board = "XXXXXX\n\
+                 XTM  X\n\
+                 XXXX X\n\
+                 X   GX\n\
+                 XXXXXX\n";
+    let game = theseus::Game::from_board(board)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+    assert!(game.theseus_cornered());
+
+    // An opening below lets him slip away
+    let board = "XXXXXX\n\
+                 XTM  X\n\
+                 X XX X\n\
+                 X   GX\n\
+                 XXXXXX\n";
+    let game = theseus::Game::from_board(board).unwrap();
+    assert!(!game.theseus_cornered());
+}
+
+#[test]
+fn test_from_annotated_board() {
+    let annotated = "# The first level\n\
+                     # by the level designer\n\
+                     \n\
+                     XXXXX\n\
+                     XT MX\n\
+                     \n\
+                     # a gap in the wall\n\
+                     X X X\n\
+                     X  GX\r\n\
+                     XXXXX\n\
+                     \n\
+                     \n";
+    let game = theseus::Game::from_annotated_board(annotated)
+        .unwrap_or_else(|e| panic!("Failed to create game from board: {}", e));
+    let plain = "XXXXX\n\
+                 XT MX\n\
+                 X X X\n\
+                 X  GX\n\
+                 XXXXX\n";
+    assert_eq!((game.width(), game.height()), (5, 5));
+    assert!(game == theseus::Game::from_board(plain).unwrap());
+
+    // The strict parser still takes comments as board rows
+    assert!(theseus::Game::from_board(annotated).is_err());
+}
+
+#[test]
+fn test_minotaur_distance_to_theseus() {
+    let board = "XXXXXXX\n\
+                 XT X MX\n\
+                 X  X  X\n\
+                 X     X\n\
+                 XXXXXGX\n";
+    let game = theseus::Game::from_board(board)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+    // The wall between them forces a detour round the bottom
+    assert_eq!(game.theseus_minotaur_distance(), 4);
+    assert_eq!(game.minotaur_distance_to_theseus(), Some(8));
+
+    // In the open the two agree
+    let open = game.with_positions((3, 1), (3, 5)).unwrap();
+    assert_eq!(open.minotaur_distance_to_theseus(), Some(4));
+    assert_eq!(open.theseus_minotaur_distance(), 4);
+
+    let sealed = "XXXXX\n\
+                  XTXMX\n\
+                  X XGX\n\
+                  XXXXX\n";
+    let game = theseus::Game::from_board(sealed).unwrap();
+    assert_eq!(game.minotaur_distance_to_theseus(), None);
+}
+
+#[test]
+fn test_save_and_load_session() {
+    use theseus::{BoardError, Command, Game};
+    let board = "XXXXXXXXX\n\
+                 XM      X\n\
+                 X K   T X\n\
+                 X      GX\n\
+                 XXXXXXXXX\n";
+    let mut game = Game::from_board(board)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+    game.set_max_turns(Some(20));
+    game.step(Command::Left);
+    game.step(Command::Left);
+    game.step(Command::Up);
+    game.undo();
+
+    let bytes = game.save_session();
+    assert_eq!(bytes[0], Game::SESSION_VERSION);
+    let mut loaded = Game::load_session(&bytes).unwrap();
+    assert!(loaded == game);
+    assert_eq!(loaded.render(), game.render());
+    assert_eq!(loaded.turns(), game.turns());
+    assert_eq!(loaded.max_turns(), Some(20));
+    let mut visited: Vec<_> = loaded.visited().collect();
+    let mut expected: Vec<_> = game.visited().collect();
+    visited.sort();
+    expected.sort();
+    assert_eq!(visited, expected);
+
+    // The history came along too
+    assert!(loaded.redo());
+    assert!(game.redo());
+    assert!(loaded == game);
+    assert!(loaded.undo() && loaded.undo() && loaded.undo());
+    assert!(!loaded.undo());
+    assert!(loaded == Game::from_board(board).unwrap());
+
+    let mut future = bytes.clone();
+    future[0] = 99;
+    assert_eq!(
+        Game::load_session(&future).err(),
+        Some(BoardError::UnknownSessionVersion(99))
+    );
+    assert_eq!(
+        Game::load_session(&bytes[..bytes.len() - 1]).err(),
+        Some(BoardError::InvalidSession)
+    );
+    assert_eq!(
+        Game::load_session(&[]).err(),
+        Some(BoardError::InvalidSession)
+    );
+
+    // A crafted session can't stand Theseus in a wall or stall every turn
+    let speed = 17 + 9 * 5;
+    let mut fast = bytes.clone();
+    fast[speed..speed + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+    assert_eq!(
+        Game::load_session(&fast).err(),
+        Some(BoardError::InvalidSession)
+    );
+    // Past the speed, turn limit, axis, wrap, diagonals, goals, portals, turn
+    let theseus_row = speed + 4 + 5 + 4 + 4;
+    let mut walled = bytes.clone();
+    walled[theseus_row..theseus_row + 8].copy_from_slice(&0u64.to_le_bytes());
+    assert!(matches!(
+        Game::load_session(&walled).err(),
+        Some(BoardError::Occupied { row: 0, .. })
+    ));
+}
+
+#[test]
+fn test_distance_to_goal() {
+    use theseus::{Command, Game, GameConfig, Position};
+    let board = "XXXXXXX\n\
+                 X     X\n\
+                 XTXGX X\n\
+                 XXXXXMX\n\
+                 XXXXXXX\n";
+    let game = Game::from_board(board)
+        .unwrap_or_else(|_| panic!("Failed to create game from board {}", board));
+    // Two columns apart, but the wall between means going up and over
+    assert_eq!(game.distance_to_goal(), 2);
+    assert_eq!(game.path_distance_to_goal(), Some(4));
+
+    // A locked door keeps him from the goal until he has the key
+    let locked = "XXXXXX\n\
+                  XTKDGX\n\
+                  XMXXXX\n\
+                  XXXXXX\n";
+    let mut game = Game::from_board(locked).unwrap();
+    assert_eq!(game.distance_to_goal(), 3);
+    assert_eq!(game.path_distance_to_goal(), None);
+    game.theseus_move(Command::Right);
+    assert_eq!(game.path_distance_to_goal(), Some(2));
+
+    // With several goals, it's the nearest one not visited yet
+    let goals = "XXXXXXX\n\
+                 XG T GX\n\
+                 X XXX X\n\
+                 X    MX\n\
+                 XXXXXXX\n";
+    let config = GameConfig {
+        allow_multiple_goals: true,
+        ..GameConfig::default()
+    };
+    let mut game = Game::from_board_with_config(goals, config).unwrap();
+    assert_eq!(game.distance_to_goal(), 2);
+    game.step(Command::Right);
+    game.step(Command::Right);
+    assert_eq!(game.goals_left(), &[Position::new(1, 1)]);
+    assert_eq!(game.distance_to_goal(), 4);
+    assert_eq!(game.path_distance_to_goal(), Some(4));
+}
+
+#[test]
+fn test_reject_lost_start() {
+    use theseus::{BoardError, Game, GameConfig, GameStatus};
+    let strict = GameConfig {
+        reject_lost_start: true,
+        ..GameConfig::default()
+    };
+    // A board file can't stack them, but JSON can
+    let overlapping = r#"{"width":5,"height":3,"rows":["XXXXX","X  GX","XXXXX"],
+        "theseus":[1,1],"minotaurs":[[1,1]],"goal":[1,3]}"#;
+    let game = Game::from_json(overlapping).unwrap();
+    assert_eq!(game.status(), GameStatus::Lose);
+    assert_eq!(
+        Game::from_json_with_config(overlapping, strict).err(),
+        Some(BoardError::StartsLost)
+    );
+
+    let board = "XXXXXX\n\
+                 XT MGX\n\
+                 XXXXXX\n";
+    assert!(Game::from_board_with_config(board, strict).is_ok());
+    let no_turns = GameConfig {
+        max_turns: Some(0),
+        ..strict
+    };
+    assert_eq!(
+        Game::from_board_with_config(board, no_turns).err(),
+        Some(BoardError::StartsLost)
+    );
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn test_input_async() {
+    use theseus::Command;
+    let mut reader = tokio::io::BufReader::new("d\n\nw\nbogus\nq\n".as_bytes());
+    assert_eq!(
+        theseus::input_async(&mut reader).await,
+        Some(Command::Right)
+    );
+    assert_eq!(theseus::input_async(&mut reader).await, Some(Command::Skip));
+    assert_eq!(theseus::input_async(&mut reader).await, Some(Command::Up));
+    assert_eq!(theseus::input_async(&mut reader).await, None);
+    assert_eq!(theseus::input_async(&mut reader).await, None);
+    // End of input
+    assert_eq!(theseus::input_async(&mut reader).await, None);
+}
+
+#[test]
+#[ignore = "needs the wasm32-unknown-unknown target; run with --ignored"]
+fn test_wasm_target_compiles() {
+    // The core, and the browser bindings, without any of the IO functions
+    let manifest_dir = env!("CARGO_MANIFEST_DIR");
+    let status = std::process::Command::new(env!("CARGO"))
+        .args(["check", "--lib", "--no-default-features"])
+        .args(["--target", "wasm32-unknown-unknown"])
+        .arg("--target-dir")
+        .arg(format!("{}/target/wasm-check", manifest_dir))
+        .current_dir(manifest_dir)
+        .status()
+        .expect("failed to run cargo");
+    assert!(status.success());
+}